@@ -51,7 +51,7 @@ async fn main(mut api: craydate::Api) -> ! {
     Ok(font) => {
       log(format!("Font height: {}", font.font_height()));
 
-      let page = font.font_page('d');
+      let page = font.font_page('d').unwrap();
       log("Got page");
       let _bitmap = page.glyph('d').unwrap().bitmap();
 
@@ -302,6 +302,13 @@ async fn main(mut api: craydate::Api) -> ! {
     }
   };
 
+  // The same checksum should be logged on the device and in the simulator. If it differs, then the
+  // deterministic math gives different results on the two.
+  log(format!(
+    "determinism checksum: {:016x}",
+    determinism_checksum()
+  ));
+
   log(format!(
     "Entering main loop at time {}",
     api.system.current_time()
@@ -341,3 +348,29 @@ async fn main(mut api: craydate::Api) -> ! {
     api.graphics.draw_fps(400 - 15, 0);
   }
 }
+
+/// Runs the deterministic math functions and types on a fixed sequence of inputs, and returns a
+/// checksum of the results.
+fn determinism_checksum() -> u64 {
+  use core::hash::Hasher;
+
+  let mut checksum = StateChecksum::new();
+  let mut random = Random::new(0x5eed);
+  let mut x = Fixed::from_ratio(1, 3);
+  for _ in 0..1000 {
+    let r = Fixed::from_bits(random.range(-(4 << 16), 4 << 16));
+    x = (x * r).sin() + (x.abs() + Fixed::ONE).sqrt() - x.cos() / (r.abs() + Fixed::ONE);
+    x = x.lerp(Fixed::from_f32(random.next_f32()), Fixed::HALF);
+    checksum.write_fixed(x);
+
+    let f = random.next_f32() * 20.0 - 10.0;
+    checksum.write_f32(sin(f));
+    checksum.write_f32(atan2(f, x.to_f32()));
+    checksum.write_f32(exp(f));
+    checksum.write_f32(pow(f.abs(), 1.5));
+    checksum.write_f32(sqrt(f.abs()));
+    checksum.write_f32(mul_add(f, f, x.to_f32()));
+    checksum.write_u32(random.next_u32());
+  }
+  checksum.finish()
+}
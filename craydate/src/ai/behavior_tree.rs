@@ -0,0 +1,386 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::time::TimeDelta;
+
+/// The result of ticking a node in a `BehaviorTree`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BehaviorStatus {
+  /// The node has completed successfully.
+  Success,
+  /// The node has completed and failed.
+  Failure,
+  /// The node has not completed yet, and will be ticked again on the next tick of the tree.
+  Running,
+}
+
+/// A value stored in a `Blackboard`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BlackboardValue {
+  Bool(bool),
+  Int(i32),
+  Float(f32),
+  Point(euclid::default::Point2D<i32>),
+}
+
+/// Values shared between the nodes of a `BehaviorTree`, such as the NPC's current target.
+///
+/// Values are keyed by `K`, which would usually be a small `enum` or a `&'static str`. Setting a key
+/// for the first time allocates, so keys that are set while ticking should be declared with an
+/// initial value when the tree is built, after which setting them never allocates.
+#[derive(Debug)]
+pub struct Blackboard<K: Ord + Copy> {
+  // Sorted by key.
+  values: Vec<(K, BlackboardValue)>,
+}
+impl<K: Ord + Copy> Blackboard<K> {
+  fn new() -> Self {
+    Blackboard { values: Vec::new() }
+  }
+
+  /// Returns the value for the `key`, or None if it has not been set.
+  pub fn get(&self, key: K) -> Option<BlackboardValue> {
+    let index = self.values.binary_search_by(|(k, _)| k.cmp(&key)).ok()?;
+    Some(self.values[index].1)
+  }
+  /// Sets the `value` for the `key`.
+  pub fn set(&mut self, key: K, value: BlackboardValue) {
+    match self.values.binary_search_by(|(k, _)| k.cmp(&key)) {
+      Ok(index) => self.values[index].1 = value,
+      Err(index) => self.values.insert(index, (key, value)),
+    }
+  }
+  /// Removes the value for the `key`.
+  pub fn remove(&mut self, key: K) {
+    if let Ok(index) = self.values.binary_search_by(|(k, _)| k.cmp(&key)) {
+      self.values.remove(index);
+    }
+  }
+
+  /// Returns the value for the `key` if it is a `BlackboardValue::Bool`.
+  pub fn get_bool(&self, key: K) -> Option<bool> {
+    match self.get(key)? {
+      BlackboardValue::Bool(b) => Some(b),
+      _ => None,
+    }
+  }
+  /// Returns the value for the `key` if it is a `BlackboardValue::Int`.
+  pub fn get_int(&self, key: K) -> Option<i32> {
+    match self.get(key)? {
+      BlackboardValue::Int(i) => Some(i),
+      _ => None,
+    }
+  }
+  /// Returns the value for the `key` if it is a `BlackboardValue::Float`.
+  pub fn get_float(&self, key: K) -> Option<f32> {
+    match self.get(key)? {
+      BlackboardValue::Float(f) => Some(f),
+      _ => None,
+    }
+  }
+  /// Returns the value for the `key` if it is a `BlackboardValue::Point`.
+  pub fn get_point(&self, key: K) -> Option<euclid::default::Point2D<i32>> {
+    match self.get(key)? {
+      BlackboardValue::Point(p) => Some(p),
+      _ => None,
+    }
+  }
+}
+
+/// An identifier for a node added to a `BehaviorTreeBuilder`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BehaviorNodeId(usize);
+
+type ActionFn<C, K> = Box<dyn FnMut(&mut C, &mut Blackboard<K>, TimeDelta) -> BehaviorStatus>;
+type ConditionFn<C, K> = Box<dyn Fn(&C, &Blackboard<K>) -> bool>;
+
+enum Node<C, K: Ord + Copy> {
+  Action(ActionFn<C, K>),
+  Condition(ConditionFn<C, K>),
+  // Children are a range in `BehaviorTree::children`.
+  Sequence { first: usize, count: usize },
+  Selector { first: usize, count: usize },
+  Invert(usize),
+  AlwaysSucceed(usize),
+  Repeat { child: usize, times: u32 },
+  Cooldown { child: usize, duration: TimeDelta },
+}
+
+/// The state of each node that persists between ticks.
+#[derive(Debug, Copy, Clone)]
+enum NodeState {
+  None,
+  // The index of the child, within the node's children, to tick next.
+  NextChild(usize),
+  // The number of times the child has completed.
+  Repeated(u32),
+  // The time left before the child can be ticked again.
+  CoolingDown(TimeDelta),
+}
+
+/// Builds a `BehaviorTree` from its nodes.
+///
+/// Each method adds a node and returns its id, which is given to the methods that add parent
+/// nodes. Once the whole tree is added, `build()` is called with the id of the root node.
+pub struct BehaviorTreeBuilder<C, K: Ord + Copy> {
+  nodes: Vec<Node<C, K>>,
+  children: Vec<usize>,
+  blackboard: Blackboard<K>,
+}
+impl<C, K: Ord + Copy> BehaviorTreeBuilder<C, K> {
+  pub fn new() -> Self {
+    BehaviorTreeBuilder {
+      nodes: Vec::new(),
+      children: Vec::new(),
+      blackboard: Blackboard::new(),
+    }
+  }
+
+  fn add(&mut self, node: Node<C, K>) -> BehaviorNodeId {
+    self.nodes.push(node);
+    BehaviorNodeId(self.nodes.len() - 1)
+  }
+  fn add_children(&mut self, children: &[BehaviorNodeId]) -> (usize, usize) {
+    let first = self.children.len();
+    self.children.extend(children.iter().map(|c| c.0));
+    (first, children.len())
+  }
+
+  /// Adds a leaf node that runs the `action` function each time it is ticked.
+  ///
+  /// The function receives the context that the tree is ticked with, such as the NPC itself, the
+  /// tree's `Blackboard`, and the time elapsed since the last tick.
+  pub fn action<F>(&mut self, action: F) -> BehaviorNodeId
+  where
+    F: FnMut(&mut C, &mut Blackboard<K>, TimeDelta) -> BehaviorStatus + 'static,
+  {
+    self.add(Node::Action(Box::new(action)))
+  }
+  /// Adds a leaf node that succeeds if the `condition` function returns true, and fails otherwise.
+  pub fn condition<F>(&mut self, condition: F) -> BehaviorNodeId
+  where
+    F: Fn(&C, &Blackboard<K>) -> bool + 'static,
+  {
+    self.add(Node::Condition(Box::new(condition)))
+  }
+  /// Adds a node that ticks its `children` in order until one of them fails, and succeeds if none
+  /// of them fail.
+  ///
+  /// If a child is running, the sequence is running, and it continues from that child on the next
+  /// tick.
+  pub fn sequence(&mut self, children: &[BehaviorNodeId]) -> BehaviorNodeId {
+    let (first, count) = self.add_children(children);
+    self.add(Node::Sequence { first, count })
+  }
+  /// Adds a node that ticks its `children` in order until one of them succeeds, and fails if none
+  /// of them succeed.
+  ///
+  /// If a child is running, the selector is running, and it continues from that child on the next
+  /// tick.
+  pub fn selector(&mut self, children: &[BehaviorNodeId]) -> BehaviorNodeId {
+    let (first, count) = self.add_children(children);
+    self.add(Node::Selector { first, count })
+  }
+  /// Adds a node that succeeds when its `child` fails, and fails when its `child` succeeds.
+  pub fn invert(&mut self, child: BehaviorNodeId) -> BehaviorNodeId {
+    self.add(Node::Invert(child.0))
+  }
+  /// Adds a node that succeeds when its `child` completes, whether the child succeeded or failed.
+  pub fn always_succeed(&mut self, child: BehaviorNodeId) -> BehaviorNodeId {
+    self.add(Node::AlwaysSucceed(child.0))
+  }
+  /// Adds a node that runs its `child` to completion `times` times, and then succeeds. It fails if
+  /// the child fails.
+  pub fn repeat(&mut self, child: BehaviorNodeId, times: u32) -> BehaviorNodeId {
+    self.add(Node::Repeat { child: child.0, times })
+  }
+  /// Adds a node that fails without ticking its `child` for `duration` after the child completes.
+  pub fn cooldown(&mut self, child: BehaviorNodeId, duration: TimeDelta) -> BehaviorNodeId {
+    self.add(Node::Cooldown {
+      child: child.0,
+      duration,
+    })
+  }
+
+  /// Sets an initial value in the tree's `Blackboard`.
+  ///
+  /// Keys that will be set while ticking the tree should be declared here, so that ticking does
+  /// not need to allocate.
+  pub fn declare(&mut self, key: K, value: BlackboardValue) -> &mut Self {
+    self.blackboard.set(key, value);
+    self
+  }
+
+  /// Builds the `BehaviorTree` with the given `root` node.
+  pub fn build(self, root: BehaviorNodeId) -> BehaviorTree<C, K> {
+    let states = alloc::vec![NodeState::None; self.nodes.len()];
+    BehaviorTree {
+      nodes: self.nodes,
+      children: self.children,
+      states,
+      root: root.0,
+      blackboard: self.blackboard,
+    }
+  }
+}
+impl<C, K: Ord + Copy> Default for BehaviorTreeBuilder<C, K> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A behavior tree, for deciding what an NPC does each frame.
+///
+/// The tree is made of leaf nodes that run game code (actions and conditions) and of nodes that
+/// decide which of their children to run (sequences, selectors, and decorators that change the
+/// result of a single child). Each call to `tick()` runs the tree from the root until a node is
+/// `BehaviorStatus::Running` or the root completes. A running node is ticked again on the next
+/// call, and the sequences and selectors above it remember where they were.
+///
+/// The tree is built with a `BehaviorTreeBuilder`. The nodes and their state are stored in arrays
+/// that are allocated when the tree is built, so ticking the tree does not allocate, unless a node
+/// sets a `Blackboard` key that was not declared.
+///
+/// The type `C` is the context given to actions and conditions, such as the NPC being controlled,
+/// and `K` is the type of keys in the `Blackboard`.
+pub struct BehaviorTree<C, K: Ord + Copy> {
+  nodes: Vec<Node<C, K>>,
+  children: Vec<usize>,
+  states: Vec<NodeState>,
+  root: usize,
+  blackboard: Blackboard<K>,
+}
+impl<C, K: Ord + Copy> BehaviorTree<C, K> {
+  /// Ticks the tree, running its nodes with the `context`. The `dt` is the time elapsed since the
+  /// last tick, which should be a fixed amount if the game is updated with a fixed timestep.
+  ///
+  /// Returns the status of the root node. When the root completes, the next tick begins again from
+  /// the start of the tree.
+  pub fn tick(&mut self, context: &mut C, dt: TimeDelta) -> BehaviorStatus {
+    // Cooldowns count down on every tick of the tree, whether their node is reached or not, so that
+    // they expire in game time.
+    for s in &mut self.states {
+      if let NodeState::CoolingDown(left) = *s {
+        let left = left.total_whole_milliseconds() - dt.total_whole_milliseconds();
+        *s = if left > 0 {
+          NodeState::CoolingDown(TimeDelta::from_milliseconds(left))
+        } else {
+          NodeState::None
+        };
+      }
+    }
+    self.tick_node(self.root, context, dt)
+  }
+
+  /// Forgets the state of all running nodes, so that the next tick begins again from the start of
+  /// the tree. Cooldowns are also reset.
+  pub fn reset(&mut self) {
+    for s in &mut self.states {
+      *s = NodeState::None;
+    }
+  }
+
+  /// The values shared between the nodes of the tree.
+  pub fn blackboard(&self) -> &Blackboard<K> {
+    &self.blackboard
+  }
+  /// The values shared between the nodes of the tree, which can be changed by the game.
+  pub fn blackboard_mut(&mut self) -> &mut Blackboard<K> {
+    &mut self.blackboard
+  }
+
+  fn tick_node(&mut self, index: usize, context: &mut C, dt: TimeDelta) -> BehaviorStatus {
+    match self.nodes[index] {
+      Node::Action(ref mut action) => action(context, &mut self.blackboard, dt),
+      Node::Condition(ref condition) => {
+        if condition(context, &self.blackboard) {
+          BehaviorStatus::Success
+        } else {
+          BehaviorStatus::Failure
+        }
+      }
+      Node::Sequence { first, count } => {
+        self.tick_composite(index, first, count, BehaviorStatus::Success, context, dt)
+      }
+      Node::Selector { first, count } => {
+        self.tick_composite(index, first, count, BehaviorStatus::Failure, context, dt)
+      }
+      Node::Invert(child) => match self.tick_node(child, context, dt) {
+        BehaviorStatus::Success => BehaviorStatus::Failure,
+        BehaviorStatus::Failure => BehaviorStatus::Success,
+        BehaviorStatus::Running => BehaviorStatus::Running,
+      },
+      Node::AlwaysSucceed(child) => match self.tick_node(child, context, dt) {
+        BehaviorStatus::Running => BehaviorStatus::Running,
+        _ => BehaviorStatus::Success,
+      },
+      Node::Repeat { child, times } => {
+        let mut repeated = match self.states[index] {
+          NodeState::Repeated(r) => r,
+          _ => 0,
+        };
+        // Each completion of the child starts it again on the next tick, so that a repeat of an
+        // action which completes immediately does not run it many times in one frame.
+        let status = match self.tick_node(child, context, dt) {
+          BehaviorStatus::Running => BehaviorStatus::Running,
+          BehaviorStatus::Failure => {
+            repeated = 0;
+            BehaviorStatus::Failure
+          }
+          BehaviorStatus::Success => {
+            repeated += 1;
+            if repeated >= times {
+              repeated = 0;
+              BehaviorStatus::Success
+            } else {
+              BehaviorStatus::Running
+            }
+          }
+        };
+        self.states[index] = NodeState::Repeated(repeated);
+        status
+      }
+      Node::Cooldown { child, duration } => {
+        if let NodeState::CoolingDown(_) = self.states[index] {
+          return BehaviorStatus::Failure;
+        }
+        let status = self.tick_node(child, context, dt);
+        if status != BehaviorStatus::Running {
+          self.states[index] = NodeState::CoolingDown(duration);
+        }
+        status
+      }
+    }
+  }
+
+  /// Ticks the children of a sequence or selector, which stops at the first child that does not
+  /// return `keep_going`.
+  fn tick_composite(
+    &mut self,
+    index: usize,
+    first: usize,
+    count: usize,
+    keep_going: BehaviorStatus,
+    context: &mut C,
+    dt: TimeDelta,
+  ) -> BehaviorStatus {
+    let mut next = match self.states[index] {
+      NodeState::NextChild(n) => n,
+      _ => 0,
+    };
+    while next < count {
+      let status = self.tick_node(self.children[first + next], context, dt);
+      if status == BehaviorStatus::Running {
+        self.states[index] = NodeState::NextChild(next);
+        return BehaviorStatus::Running;
+      }
+      if status != keep_going {
+        self.states[index] = NodeState::None;
+        return status;
+      }
+      next += 1;
+    }
+    self.states[index] = NodeState::None;
+    keep_going
+  }
+}
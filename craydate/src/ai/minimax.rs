@@ -0,0 +1,241 @@
+use alloc::vec::Vec;
+
+use crate::time::{HighResolutionTimer, TimeDelta};
+
+/// How many positions to visit between checks of the time budget, as reading the timer is not free.
+const NODES_PER_TIME_CHECK: u32 = 64;
+
+/// A score larger than any that `MinimaxGame::evaluate()` should return. It is not `i32::MAX` so
+/// that it can be negated without overflowing.
+const INFINITE_SCORE: i32 = i32::MAX - 1;
+
+/// A turn-based, two player game that can be searched by `Minimax`.
+///
+/// The game state is cloned for each move that is searched, so it should be small and cheap to
+/// clone, such as a board held in a fixed-size array.
+pub trait MinimaxGame: Clone {
+  /// A move that can be made by a player.
+  type Move: Clone;
+
+  /// Appends all the moves that the player whose turn it is can make to `moves`.
+  ///
+  /// If no moves are appended, the game is over and `evaluate()` will be used to score it. Moves
+  /// that are more likely to be good should be appended first, as they make the search faster.
+  fn moves(&self, moves: &mut Vec<Self::Move>);
+  /// Returns the state of the game after the player whose turn it is makes the move `m`.
+  fn apply(&self, m: &Self::Move) -> Self;
+  /// Scores the game state, from the point of view of the player whose turn it is.
+  ///
+  /// Higher scores are better for that player, and the score for the other player is the negative
+  /// of it. The score must be between `-(i32::MAX - 1)` and `i32::MAX - 1`.
+  fn evaluate(&self) -> i32;
+}
+
+/// The result of running a `Minimax` search for a while with `Minimax::search()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MinimaxStatus {
+  /// The search ran out of time for this frame, and should be resumed by calling `search()` again.
+  Searching,
+  /// The search has reached its maximum depth, or has no choice to make, and `best_move()` will not
+  /// change again.
+  Finished,
+}
+
+/// One position being searched, which is waiting on the scores of the positions after each of its
+/// moves.
+#[derive(Debug)]
+struct Frame<G: MinimaxGame> {
+  state: G,
+  moves: Vec<G::Move>,
+  // The index in `moves` of the next move to be searched.
+  next: usize,
+  // How many more moves deep to search after this position.
+  depth: u32,
+  alpha: i32,
+  beta: i32,
+  best_score: i32,
+  best_move: usize,
+}
+
+/// A search for the best move in a turn-based game, which can be spread across many frames.
+///
+/// The search uses minimax with alpha-beta pruning, and deepens iteratively: it first searches every
+/// move 1 turn ahead, then 2 turns ahead, and so on up to a maximum depth. Each call to `search()`
+/// continues where the last one stopped until the frame's time budget is used up, so a game can
+/// keep animating while the computer player thinks. At any time, `best_move()` gives the best move
+/// found by the deepest search that has completed, so the game can also stop early and play it.
+///
+/// The stack of positions being searched is kept in the `Minimax` rather than on the call stack, so
+/// the search can be paused anywhere in it.
+#[derive(Debug)]
+pub struct Minimax<G: MinimaxGame> {
+  root: G,
+  // The moves at the root, with the best from the last completed depth moved to the front.
+  root_moves: Vec<G::Move>,
+  max_depth: u32,
+  // The depth being searched currently.
+  depth: u32,
+  stack: Vec<Frame<G>>,
+  best: Option<(G::Move, i32)>,
+  completed_depth: u32,
+  nodes: u32,
+}
+impl<G: MinimaxGame> Minimax<G> {
+  /// Starts a search for the best move in the game state `root`, looking up to `max_depth` turns
+  /// ahead.
+  ///
+  /// No searching is done until `search()` is called.
+  pub fn new(root: G, max_depth: u32) -> Self {
+    assert!(max_depth > 0);
+    let mut root_moves = Vec::new();
+    root.moves(&mut root_moves);
+    Minimax {
+      root,
+      root_moves,
+      max_depth,
+      depth: 0,
+      stack: Vec::new(),
+      best: None,
+      completed_depth: 0,
+      nodes: 0,
+    }
+  }
+
+  /// Runs the search until the `timer` shows that `budget` has elapsed, or until the search is
+  /// finished.
+  ///
+  /// The `timer`'s elapsed time includes everything done since it was started with
+  /// `System::start_timer()`, so starting the timer at the beginning of each frame makes `budget`
+  /// the portion of the frame that can be spent before the search yields.
+  pub fn search(&mut self, timer: &HighResolutionTimer, budget: TimeDelta) -> MinimaxStatus {
+    let budget_micros = budget.total_whole_milliseconds().max(0) as u32 * 1000;
+    self.run(|| timer.elapsed_microseconds() >= budget_micros)
+  }
+
+  /// The best move found by the deepest search that has completed so far.
+  ///
+  /// Before any depth is completed, this is the best move found so far at the first depth, if any.
+  /// Returns None only if no move has been searched yet, or if the game has no moves to make.
+  pub fn best_move(&self) -> Option<&G::Move> {
+    self.best.as_ref().map(|(m, _)| m)
+  }
+  /// The score of `best_move()`, from the point of view of the player making the move.
+  pub fn best_score(&self) -> Option<i32> {
+    self.best.as_ref().map(|(_, s)| *s)
+  }
+  /// How many turns ahead the search has fully looked so far.
+  pub fn completed_depth(&self) -> u32 {
+    self.completed_depth
+  }
+  /// The number of game states the search has visited so far.
+  pub fn nodes_searched(&self) -> u32 {
+    self.nodes
+  }
+  /// Returns whether the search has finished, in which case `best_move()` will not change again.
+  pub fn is_finished(&self) -> bool {
+    // With a single move to make there is nothing to choose, so it's done once the move is known.
+    self.root_moves.is_empty()
+      || self.completed_depth >= self.max_depth
+      || (self.root_moves.len() == 1 && self.best.is_some())
+  }
+
+  fn run<F: FnMut() -> bool>(&mut self, mut out_of_time: F) -> MinimaxStatus {
+    loop {
+      if self.is_finished() {
+        self.stack.clear();
+        return MinimaxStatus::Finished;
+      }
+      if self.stack.is_empty() {
+        self.start_depth();
+      }
+
+      self.step();
+
+      if self.nodes.is_multiple_of(NODES_PER_TIME_CHECK) && out_of_time() {
+        return MinimaxStatus::Searching;
+      }
+    }
+  }
+
+  /// Begins searching at the next depth. The root moves are already ordered with the best move from
+  /// the last depth first.
+  fn start_depth(&mut self) {
+    self.depth = self.completed_depth + 1;
+    self.stack.push(Frame {
+      state: self.root.clone(),
+      moves: self.root_moves.clone(),
+      next: 0,
+      depth: self.depth,
+      alpha: -INFINITE_SCORE,
+      beta: INFINITE_SCORE,
+      best_score: -INFINITE_SCORE,
+      best_move: 0,
+    });
+  }
+
+  /// Visits one game state, either by scoring it or by pushing it onto the stack to search its
+  /// moves. When a position has no more moves to search, it is popped off the stack and its score
+  /// is given to the position before it.
+  fn step(&mut self) {
+    let top = self.stack.last_mut().unwrap();
+    if top.next < top.moves.len() && top.alpha < top.beta {
+      let child = top.state.apply(&top.moves[top.next]);
+      top.next += 1;
+      self.nodes = self.nodes.wrapping_add(1);
+
+      let mut moves = Vec::new();
+      if top.depth > 1 {
+        child.moves(&mut moves);
+      }
+      if moves.is_empty() {
+        let score = -child.evaluate();
+        self.give_score(score);
+      } else {
+        let (depth, alpha, beta) = (top.depth - 1, -top.beta, -top.alpha);
+        self.stack.push(Frame {
+          state: child,
+          moves,
+          next: 0,
+          depth,
+          alpha,
+          beta,
+          best_score: -INFINITE_SCORE,
+          best_move: 0,
+        });
+      }
+    } else {
+      let frame = self.stack.pop().unwrap();
+      if self.stack.is_empty() {
+        self.finish_depth(frame);
+      } else {
+        self.give_score(-frame.best_score);
+      }
+    }
+  }
+
+  /// Records the `score` of the last move searched from the position at the top of the stack.
+  fn give_score(&mut self, score: i32) {
+    let is_root = self.stack.len() == 1;
+    let top = self.stack.last_mut().unwrap();
+    if score > top.best_score {
+      top.best_score = score;
+      top.best_move = top.next - 1;
+      // Until the first depth completes, keep track of the best move seen so there's always
+      // something to play.
+      if is_root && self.completed_depth == 0 {
+        self.best = Some((top.moves[top.best_move].clone(), score));
+      }
+    }
+    if score > top.alpha {
+      top.alpha = score;
+    }
+  }
+
+  fn finish_depth(&mut self, root: Frame<G>) {
+    self.completed_depth = self.depth;
+    self.best = Some((root.moves[root.best_move].clone(), root.best_score));
+    // Search the best move first at the next depth, so that the pruning is most effective.
+    let best = self.root_moves.remove(root.best_move);
+    self.root_moves.insert(0, best);
+  }
+}
@@ -0,0 +1,9 @@
+pub(crate) mod behavior_tree;
+pub(crate) mod minimax;
+pub(crate) mod steering;
+
+pub use behavior_tree::{
+  BehaviorNodeId, BehaviorStatus, BehaviorTree, BehaviorTreeBuilder, Blackboard, BlackboardValue,
+};
+pub use minimax::{Minimax, MinimaxGame, MinimaxStatus};
+pub use steering::{SpatialHash, SteeringAgent, SteeringScalar, Wander};
@@ -0,0 +1,364 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::math::Fixed;
+use crate::time::TimeDelta;
+
+/// A number type that steering can be done with, for positions, velocities and distances.
+///
+/// This is implemented for `f32`, and for `Fixed`, whose arithmetic gives exactly the same results
+/// on the device and in the simulator. Steering with `Fixed` can be part of game state that must be
+/// reproduced exactly, such as in replays or lockstep multiplayer.
+pub trait SteeringScalar:
+  Copy
+  + PartialOrd
+  + core::ops::Add<Output = Self>
+  + core::ops::Sub<Output = Self>
+  + core::ops::Mul<Output = Self>
+  + core::ops::Div<Output = Self>
+  + core::ops::Neg<Output = Self>
+{
+  /// The value 0.
+  const ZERO: Self;
+  /// The value 1.
+  const ONE: Self;
+
+  /// Converts a whole number.
+  fn from_i32(i: i32) -> Self;
+  /// Converts a length of time to a number of seconds.
+  fn from_time(dt: TimeDelta) -> Self;
+  /// Returns the length of the vector (`x`, `y`).
+  fn hypot(x: Self, y: Self) -> Self;
+  /// Returns the sine and cosine of the value, in radians.
+  fn sin_cos(self) -> (Self, Self);
+  /// Returns the largest whole number less than or equal to the value.
+  fn floor_i32(self) -> i32;
+}
+impl SteeringScalar for f32 {
+  const ZERO: f32 = 0.0;
+  const ONE: f32 = 1.0;
+
+  fn from_i32(i: i32) -> f32 {
+    i as f32
+  }
+  fn from_time(dt: TimeDelta) -> f32 {
+    dt.to_seconds()
+  }
+  fn hypot(x: f32, y: f32) -> f32 {
+    crate::math::sqrt(x * x + y * y)
+  }
+  fn sin_cos(self) -> (f32, f32) {
+    crate::math::sin_cos(self)
+  }
+  fn floor_i32(self) -> i32 {
+    // Casting rounds towards zero, so step negative values down.
+    let truncated = self as i32;
+    if truncated as f32 > self {
+      truncated - 1
+    } else {
+      truncated
+    }
+  }
+}
+impl SteeringScalar for Fixed {
+  const ZERO: Fixed = Fixed::ZERO;
+  const ONE: Fixed = Fixed::ONE;
+
+  fn from_i32(i: i32) -> Fixed {
+    Fixed::from_i32(i)
+  }
+  fn from_time(dt: TimeDelta) -> Fixed {
+    Fixed::from_ratio(dt.total_whole_milliseconds(), 1000)
+  }
+  fn hypot(x: Fixed, y: Fixed) -> Fixed {
+    // The squares are summed in 64 bits, as they would overflow a `Fixed` for lengths over 181.
+    let x = (x.to_bits() as i64).unsigned_abs();
+    let y = (y.to_bits() as i64).unsigned_abs();
+    Fixed::from_bits((x * x + y * y).isqrt().min(i32::MAX as u64) as i32)
+  }
+  fn sin_cos(self) -> (Fixed, Fixed) {
+    (self.sin(), self.cos())
+  }
+  fn floor_i32(self) -> i32 {
+    self.floor()
+  }
+}
+
+fn zero<T: SteeringScalar>() -> euclid::default::Vector2D<T> {
+  euclid::vec2(T::ZERO, T::ZERO)
+}
+fn is_zero<T: SteeringScalar>(v: euclid::default::Vector2D<T>) -> bool {
+  v.x == T::ZERO && v.y == T::ZERO
+}
+fn length<T: SteeringScalar>(v: euclid::default::Vector2D<T>) -> T {
+  T::hypot(v.x, v.y)
+}
+/// Returns `v` with a length of 1. The length of `v` must not be zero.
+fn normalize<T: SteeringScalar>(v: euclid::default::Vector2D<T>) -> euclid::default::Vector2D<T> {
+  v / length(v)
+}
+fn with_max_length<T: SteeringScalar>(
+  v: euclid::default::Vector2D<T>,
+  max: T,
+) -> euclid::default::Vector2D<T> {
+  let len = length(v);
+  if len > max {
+    // Dividing first keeps the values small, so a `Fixed` does not overflow.
+    v / len * max
+  } else {
+    v
+  }
+}
+
+/// A moving object, such as an enemy or an animal, whose movement is decided by steering behaviors.
+///
+/// Each steering behavior returns a force, which is the change in velocity the agent wants in order
+/// to do that behavior. The forces of multiple behaviors can be weighted and added together, and
+/// then given to `apply_force()` to move the agent.
+///
+/// Positions, velocities and distances are `f32` by default. A `SteeringAgent<Fixed>` uses `Fixed`
+/// instead, so that agents move exactly the same way on the device and in the simulator.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SteeringAgent<T = f32> {
+  pub position: euclid::default::Point2D<T>,
+  pub velocity: euclid::default::Vector2D<T>,
+  /// The fastest the agent can move, in units per second.
+  pub max_speed: T,
+  /// The largest change in velocity the agent can make, in units per second per second.
+  pub max_force: T,
+}
+impl<T: SteeringScalar> SteeringAgent<T> {
+  /// Constructs an agent at `position` which is not moving.
+  pub fn new(position: euclid::default::Point2D<T>, max_speed: T, max_force: T) -> Self {
+    SteeringAgent {
+      position,
+      velocity: zero(),
+      max_speed,
+      max_force,
+    }
+  }
+
+  /// Moves the agent for `dt` time after changing its velocity by the `force`.
+  ///
+  /// The `force` is limited to `max_force`, and the resulting velocity to `max_speed`.
+  pub fn apply_force(&mut self, force: euclid::default::Vector2D<T>, dt: TimeDelta) {
+    let seconds = T::from_time(dt);
+    let force = with_max_length(force, self.max_force);
+    self.velocity = with_max_length(self.velocity + force * seconds, self.max_speed);
+    self.position += self.velocity * seconds;
+  }
+
+  /// The force to turn the agent's velocity towards a desired velocity.
+  fn steer_towards(&self, desired: euclid::default::Vector2D<T>) -> euclid::default::Vector2D<T> {
+    desired - self.velocity
+  }
+
+  /// Steers the agent towards the `target` at full speed.
+  pub fn seek(&self, target: euclid::default::Point2D<T>) -> euclid::default::Vector2D<T> {
+    let to_target = target - self.position;
+    if is_zero(to_target) {
+      return zero();
+    }
+    self.steer_towards(normalize(to_target) * self.max_speed)
+  }
+  /// Steers the agent away from the `threat` at full speed.
+  pub fn flee(&self, threat: euclid::default::Point2D<T>) -> euclid::default::Vector2D<T> {
+    let from_threat = self.position - threat;
+    if is_zero(from_threat) {
+      return zero();
+    }
+    self.steer_towards(normalize(from_threat) * self.max_speed)
+  }
+  /// Steers the agent towards the `target`, slowing down once it is within `slowing_radius` of the
+  /// target so that it comes to a stop there.
+  pub fn arrive(
+    &self,
+    target: euclid::default::Point2D<T>,
+    slowing_radius: T,
+  ) -> euclid::default::Vector2D<T> {
+    let to_target = target - self.position;
+    let distance = length(to_target);
+    if distance == T::ZERO {
+      return self.steer_towards(zero());
+    }
+    let speed = if distance < slowing_radius {
+      self.max_speed * (distance / slowing_radius)
+    } else {
+      self.max_speed
+    };
+    self.steer_towards(to_target / distance * speed)
+  }
+
+  /// Steers the agent away from `neighbors` that are within `radius` of it, more strongly the
+  /// closer they are.
+  ///
+  /// Neighbors at exactly the agent's position, such as the agent itself, are ignored.
+  pub fn separation<'a, I>(&self, neighbors: I, radius: T) -> euclid::default::Vector2D<T>
+  where
+    I: IntoIterator<Item = &'a SteeringAgent<T>>,
+    T: 'a,
+  {
+    let mut away = zero();
+    let mut count = 0;
+    for n in neighbors {
+      let from_neighbor = self.position - n.position;
+      let distance = length(from_neighbor);
+      if distance > T::ZERO && distance < radius {
+        // Weight by the inverse of the distance, so closer neighbors push harder.
+        away += from_neighbor / distance / distance;
+        count += 1;
+      }
+    }
+    if count == 0 || is_zero(away) {
+      return zero();
+    }
+    self.steer_towards(normalize(away) * self.max_speed)
+  }
+  /// Steers the agent to move in the same direction as the `neighbors`, which should not include
+  /// the agent itself.
+  pub fn alignment<'a, I>(&self, neighbors: I) -> euclid::default::Vector2D<T>
+  where
+    I: IntoIterator<Item = &'a SteeringAgent<T>>,
+    T: 'a,
+  {
+    let mut sum = zero();
+    for n in neighbors {
+      sum += n.velocity;
+    }
+    if is_zero(sum) {
+      return zero();
+    }
+    self.steer_towards(normalize(sum) * self.max_speed)
+  }
+  /// Steers the agent towards the center of the `neighbors`, which should not include the agent
+  /// itself.
+  pub fn cohesion<'a, I>(&self, neighbors: I) -> euclid::default::Vector2D<T>
+  where
+    I: IntoIterator<Item = &'a SteeringAgent<T>>,
+    T: 'a,
+  {
+    // Sum the offsets from the agent rather than the positions, which keeps the sum small enough
+    // for a `Fixed`.
+    let mut sum = zero();
+    let mut count = 0;
+    for n in neighbors {
+      sum += n.position - self.position;
+      count += 1;
+    }
+    if count == 0 {
+      return zero();
+    }
+    self.seek(self.position + sum / T::from_i32(count))
+  }
+}
+
+/// Steers a `SteeringAgent` along a smoothly changing random path, for aimless movement such as
+/// ambient animals.
+///
+/// The agent seeks a point on a circle projected in front of it, and the point moves a small random
+/// amount around the circle each time the behavior is used.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Wander<T = f32> {
+  /// How far in front of the agent the circle is.
+  pub distance: T,
+  /// The radius of the circle.
+  pub radius: T,
+  /// The most the point can move around the circle in one use, in radians.
+  pub jitter: T,
+  angle: T,
+}
+impl<T: SteeringScalar> Wander<T> {
+  pub fn new(distance: T, radius: T, jitter: T) -> Self {
+    Wander {
+      distance,
+      radius,
+      jitter,
+      angle: T::ZERO,
+    }
+  }
+
+  /// Returns the force to steer the `agent` while wandering.
+  ///
+  /// The `random` value should be a new random number between -1 and 1 each time, from the game's
+  /// random number generator, and it moves the target point by up to `jitter` in either direction.
+  pub fn steer(&mut self, agent: &SteeringAgent<T>, random: T) -> euclid::default::Vector2D<T> {
+    let random = if random < -T::ONE {
+      -T::ONE
+    } else if random > T::ONE {
+      T::ONE
+    } else {
+      random
+    };
+    self.angle = self.angle + random * self.jitter;
+    let heading = if is_zero(agent.velocity) {
+      euclid::vec2(T::ONE, T::ZERO)
+    } else {
+      normalize(agent.velocity)
+    };
+    let (sin, cos) = self.angle.sin_cos();
+    let offset = euclid::vec2(cos * self.radius, sin * self.radius);
+    agent.seek(agent.position + heading * self.distance + offset)
+  }
+}
+
+/// A grid of buckets for quickly finding which objects are near a position, such as the neighbors
+/// of a `SteeringAgent`.
+///
+/// Objects are identified by an index, such as their position in the game's array of agents. As
+/// objects move, the hash is usually rebuilt each frame with `clear()` followed by `insert()` for
+/// each object. Buckets are kept when cleared, so rebuilding does not allocate once the buckets
+/// that are used have been created.
+#[derive(Debug)]
+pub struct SpatialHash<T = f32> {
+  cell_size: T,
+  // Keyed by (row, column), so that a range of keys covers part of a single row.
+  cells: BTreeMap<(i32, i32), SpatialHashBucket<T>>,
+}
+type SpatialHashBucket<T> = Vec<(usize, euclid::default::Point2D<T>)>;
+impl<T: SteeringScalar> SpatialHash<T> {
+  /// Constructs an empty `SpatialHash` whose buckets are squares with sides of `cell_size`.
+  ///
+  /// Queries are fastest when the `cell_size` is about the same as the radius that will be queried.
+  pub fn new(cell_size: T) -> Self {
+    assert!(cell_size > T::ZERO);
+    SpatialHash {
+      cell_size,
+      cells: BTreeMap::new(),
+    }
+  }
+
+  /// The (row, column) of the bucket that holds `position`.
+  fn cell(&self, position: euclid::default::Point2D<T>) -> (i32, i32) {
+    (
+      (position.y / self.cell_size).floor_i32(),
+      (position.x / self.cell_size).floor_i32(),
+    )
+  }
+
+  /// Removes all objects from the hash.
+  pub fn clear(&mut self) {
+    for bucket in self.cells.values_mut() {
+      bucket.clear();
+    }
+  }
+  /// Adds the object with the given `index` at `position`.
+  pub fn insert(&mut self, index: usize, position: euclid::default::Point2D<T>) {
+    let cell = self.cell(position);
+    self.cells.entry(cell).or_default().push((index, position));
+  }
+
+  /// Appends the index of each object within `radius` of `position` to `out`.
+  pub fn query(&self, position: euclid::default::Point2D<T>, radius: T, out: &mut Vec<usize>) {
+    let (min_row, min_column) = self.cell(position - euclid::vec2(radius, radius));
+    let (max_row, max_column) = self.cell(position + euclid::vec2(radius, radius));
+    for row in min_row..=max_row {
+      for bucket in self.cells.range((row, min_column)..=(row, max_column)).map(|(_, b)| b) {
+        for (index, p) in bucket {
+          if length(*p - position) <= radius {
+            out.push(*index);
+          }
+        }
+      }
+    }
+  }
+}
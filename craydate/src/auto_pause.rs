@@ -0,0 +1,186 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::inputs::Crank;
+use crate::system_event::SystemEvent;
+
+/// A reason that an `AutoPause` has paused the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PauseReason {
+  /// The device was locked.
+  Lock,
+  /// The system menu was opened.
+  Menu,
+  /// The crank was docked, in a game that needs the crank to play.
+  CrankDocked,
+}
+
+/// A change in whether the game is paused, returned from `AutoPause::handle_event()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PauseChange {
+  /// The game was not paused, and now is, for the given reason.
+  Paused(PauseReason),
+  /// Every reason for the game to be paused has ended, and it is no longer paused.
+  Resumed,
+}
+
+/// A policy which pauses gameplay when the device is locked, the system menu is opened, or the
+/// crank is docked, and resumes it once all of those have ended.
+///
+/// Pass every `SystemEvent` to `handle_event()`. Gameplay systems, such as timers, music, and a
+/// fixed timestep loop, can be paused and resumed by closures registered with `on_pause()` and
+/// `on_resume()`, or by checking `is_paused()` each frame. This replaces the handling of these
+/// lifecycle events that each game would otherwise write for itself.
+///
+/// Each reason can be turned on or off. By default, the game is paused for the device being locked
+/// and for the system menu, but not for the crank being docked, as not every game uses the crank.
+pub struct AutoPause {
+  on_lock: bool,
+  on_menu: bool,
+  on_crank_dock: bool,
+  // Whether each reason is currently happening, even if the game is not set to pause for it.
+  locked: bool,
+  in_menu: bool,
+  crank_docked: bool,
+  pause_hooks: Vec<Box<dyn FnMut(PauseReason)>>,
+  resume_hooks: Vec<Box<dyn FnMut()>>,
+}
+impl AutoPause {
+  /// Constructs an `AutoPause` which pauses when the device is locked or the system menu is open.
+  pub fn new() -> Self {
+    AutoPause {
+      on_lock: true,
+      on_menu: true,
+      on_crank_dock: false,
+      locked: false,
+      in_menu: false,
+      crank_docked: false,
+      pause_hooks: Vec::new(),
+      resume_hooks: Vec::new(),
+    }
+  }
+
+  /// Sets whether the game is paused while the device is locked.
+  ///
+  /// If the device is locked, this can pause or resume the game, which runs the registered
+  /// closures and returns the change, as with `handle_event()`.
+  pub fn set_pause_on_lock(&mut self, pause: bool) -> Option<PauseChange> {
+    let was_paused = self.is_paused();
+    self.on_lock = pause;
+    self.transition(was_paused, PauseReason::Lock)
+  }
+  /// Sets whether the game is paused while the system menu is open.
+  ///
+  /// If the menu is open, this can pause or resume the game, which runs the registered closures and
+  /// returns the change, as with `handle_event()`.
+  pub fn set_pause_on_menu(&mut self, pause: bool) -> Option<PauseChange> {
+    let was_paused = self.is_paused();
+    self.on_menu = pause;
+    self.transition(was_paused, PauseReason::Menu)
+  }
+  /// Sets whether the game is paused while the crank is docked.
+  ///
+  /// If the crank is docked, this can pause or resume the game, which runs the registered closures
+  /// and returns the change, as with `handle_event()`.
+  pub fn set_pause_on_crank_dock(&mut self, pause: bool) -> Option<PauseChange> {
+    let was_paused = self.is_paused();
+    self.on_crank_dock = pause;
+    self.transition(was_paused, PauseReason::CrankDocked)
+  }
+
+  /// Registers a closure to run when the game is paused, such as to stop timers or music.
+  pub fn on_pause<F: FnMut(PauseReason) + 'static>(&mut self, hook: F) {
+    self.pause_hooks.push(Box::new(hook));
+  }
+  /// Registers a closure to run when the game is resumed, such as to restart timers or music.
+  pub fn on_resume<F: FnMut() + 'static>(&mut self, hook: F) {
+    self.resume_hooks.push(Box::new(hook));
+  }
+
+  /// Whether the game is paused.
+  pub fn is_paused(&self) -> bool {
+    self.is_paused_for(PauseReason::Lock)
+      || self.is_paused_for(PauseReason::Menu)
+      || self.is_paused_for(PauseReason::CrankDocked)
+  }
+  /// Whether the game is paused for the given `reason`. It may be paused for more than one reason
+  /// at once.
+  pub fn is_paused_for(&self, reason: PauseReason) -> bool {
+    match reason {
+      PauseReason::Lock => self.on_lock && self.locked,
+      PauseReason::Menu => self.on_menu && self.in_menu,
+      PauseReason::CrankDocked => self.on_crank_dock && self.crank_docked,
+    }
+  }
+
+  /// Updates the pause state from a system event, and runs the registered `on_pause()` or
+  /// `on_resume()` closures if the game was paused or resumed by it.
+  ///
+  /// Returns the change in the pause state, if there was one. While paused, the game should
+  /// continue to draw each `SystemEvent::NextFrame`, such as a pause screen, but not advance its
+  /// gameplay.
+  pub fn handle_event(&mut self, event: &SystemEvent) -> Option<PauseChange> {
+    let was_paused = self.is_paused();
+    let reason = match event {
+      SystemEvent::WillLock => {
+        self.locked = true;
+        PauseReason::Lock
+      }
+      SystemEvent::DidUnlock => {
+        self.locked = false;
+        PauseReason::Lock
+      }
+      SystemEvent::WillPause => {
+        self.in_menu = true;
+        PauseReason::Menu
+      }
+      SystemEvent::WillResume => {
+        self.in_menu = false;
+        PauseReason::Menu
+      }
+      SystemEvent::NextFrame { inputs, .. } => {
+        self.crank_docked = matches!(inputs.crank(), Crank::Docked);
+        PauseReason::CrankDocked
+      }
+      _ => return None,
+    };
+    self.transition(was_paused, reason)
+  }
+
+  /// Runs the registered closures if the game has been paused or resumed since `was_paused`, by a
+  /// change to the `reason`, and returns the change.
+  fn transition(&mut self, was_paused: bool, reason: PauseReason) -> Option<PauseChange> {
+    match (was_paused, self.is_paused()) {
+      (false, true) => {
+        for hook in &mut self.pause_hooks {
+          hook(reason)
+        }
+        Some(PauseChange::Paused(reason))
+      }
+      (true, false) => {
+        for hook in &mut self.resume_hooks {
+          hook()
+        }
+        Some(PauseChange::Resumed)
+      }
+      _ => None,
+    }
+  }
+}
+impl Default for AutoPause {
+  fn default() -> Self {
+    AutoPause::new()
+  }
+}
+impl core::fmt::Debug for AutoPause {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("AutoPause")
+      .field("on_lock", &self.on_lock)
+      .field("on_menu", &self.on_menu)
+      .field("on_crank_dock", &self.on_crank_dock)
+      .field("locked", &self.locked)
+      .field("in_menu", &self.in_menu)
+      .field("crank_docked", &self.crank_docked)
+      .finish()
+  }
+}
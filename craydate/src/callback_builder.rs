@@ -1,4 +1,4 @@
-use crate::callbacks::Callbacks;
+use crate::callbacks::{CallbackMeta, Callbacks};
 
 pub enum NoNull {}
 pub enum AllowNull {}
@@ -17,6 +17,7 @@ pub struct CallbackBuilder<
 > {
   callbacks: Option<&'a mut Callbacks<T>>,
   cb: Option<F>,
+  meta: CallbackMeta,
   _marker: core::marker::PhantomData<(&'a u8, T, F, Rule, State)>,
 }
 impl<'a> CallbackBuilder<'a, (), fn(()), AllowNull, Unconstructed> {
@@ -25,6 +26,7 @@ impl<'a> CallbackBuilder<'a, (), fn(()), AllowNull, Unconstructed> {
     CallbackBuilder {
       callbacks: None,
       cb: None,
+      meta: CallbackMeta::default(),
       _marker: core::marker::PhantomData,
     }
   }
@@ -35,6 +37,7 @@ impl<'a, T, F: Fn(T) + 'static, Rule> CallbackBuilder<'a, T, F, Rule, Unconstruc
     CallbackBuilder {
       callbacks: Some(callbacks),
       cb: None,
+      meta: CallbackMeta::default(),
       _marker: core::marker::PhantomData,
     }
   }
@@ -46,13 +49,28 @@ impl<'a, T, F: Fn(T) + 'static, Rule> CallbackBuilder<'a, T, F, Rule, WithCallac
     CallbackBuilder {
       callbacks: self.callbacks,
       cb: Some(cb),
+      meta: self.meta,
       _marker: core::marker::PhantomData,
     }
   }
 }
 impl<'a, T, F: Fn(T) + 'static, Rule> CallbackBuilder<'a, T, F, Rule, Constructed> {
-  pub(crate) fn into_inner(self) -> Option<(&'a mut Callbacks<T>, F)> {
-    self.callbacks.zip(self.cb)
+  /// Gives the callback a label, which is shown when listing callbacks with `Callbacks::iter()`,
+  /// to help with debugging.
+  pub fn label(mut self, label: &'static str) -> Self {
+    self.meta.label = Some(label);
+    self
+  }
+  /// Gives the callback a tag, which can be used to unregister it together with other callbacks
+  /// with the same tag through `Callbacks::unregister_tag()`.
+  pub fn tag(mut self, tag: &'static str) -> Self {
+    self.meta.tag = Some(tag);
+    self
+  }
+
+  pub(crate) fn into_inner(self) -> Option<(&'a mut Callbacks<T>, F, CallbackMeta)> {
+    let meta = self.meta;
+    self.callbacks.zip(self.cb).map(|(callbacks, cb)| (callbacks, cb, meta))
   }
 }
 
@@ -68,6 +86,7 @@ pub struct CallbackBuilderWithArg<
 > {
   callbacks: Option<&'a mut Callbacks<T>>,
   cb: Option<F>,
+  meta: CallbackMeta,
   _marker: core::marker::PhantomData<(&'a u8, Arg, T, Out, F, Rule, State)>,
 }
 impl<'a> CallbackBuilderWithArg<'a, (), (), (), fn((), ()) -> (), AllowNull, Unconstructed> {
@@ -76,6 +95,7 @@ impl<'a> CallbackBuilderWithArg<'a, (), (), (), fn((), ()) -> (), AllowNull, Unc
     CallbackBuilderWithArg {
       callbacks: None,
       cb: None,
+      meta: CallbackMeta::default(),
       _marker: core::marker::PhantomData,
     }
   }
@@ -90,6 +110,7 @@ impl<'a, Arg, T, Out, F: Fn(Arg, T) -> Out + 'static, Rule>
     CallbackBuilderWithArg {
       callbacks: Some(callbacks),
       cb: None,
+      meta: CallbackMeta::default(),
       _marker: core::marker::PhantomData,
     }
   }
@@ -103,6 +124,7 @@ impl<'a, Arg, T, Out, F: Fn(Arg, T) -> Out + 'static, Rule>
     CallbackBuilderWithArg {
       callbacks: self.callbacks,
       cb: Some(cb),
+      meta: self.meta,
       _marker: core::marker::PhantomData,
     }
   }
@@ -110,7 +132,21 @@ impl<'a, Arg, T, Out, F: Fn(Arg, T) -> Out + 'static, Rule>
 impl<'a, Arg, T, Out, F: Fn(Arg, T) -> Out + 'static, Rule>
   CallbackBuilderWithArg<'a, Arg, T, Out, F, Rule, Constructed>
 {
-  pub(crate) fn into_inner(self) -> Option<(&'a mut Callbacks<T>, F)> {
-    self.callbacks.zip(self.cb)
+  /// Gives the callback a label, which is shown when listing callbacks with `Callbacks::iter()`,
+  /// to help with debugging.
+  pub fn label(mut self, label: &'static str) -> Self {
+    self.meta.label = Some(label);
+    self
+  }
+  /// Gives the callback a tag, which can be used to unregister it together with other callbacks
+  /// with the same tag through `Callbacks::unregister_tag()`.
+  pub fn tag(mut self, tag: &'static str) -> Self {
+    self.meta.tag = Some(tag);
+    self
+  }
+
+  pub(crate) fn into_inner(self) -> Option<(&'a mut Callbacks<T>, F, CallbackMeta)> {
+    let meta = self.meta;
+    self.callbacks.zip(self.cb).map(|(callbacks, cb)| (callbacks, cb, meta))
   }
 }
@@ -13,11 +13,65 @@ use crate::system_event::SystemEvent;
 
 static mut CURRENT_CALLBACK: CallbackArguments = CallbackArguments::None;
 
+static mut CALLBACK_ID: usize = 0;
+/// Makes a unique id for each registered callback.
+fn make_callback_id() -> CallbackId {
+  unsafe {
+    CALLBACK_ID += 1;
+    CallbackId(CALLBACK_ID)
+  }
+}
+
+/// A unique identifier for a closure registered in a `Callbacks` collection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallbackId(usize);
+
+/// The kind of system event that a registered callback is run for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CallbackKind {
+  /// A sound source finished playing.
+  SoundSourceCompletion,
+  /// A system menu item was chosen.
+  MenuItem,
+  /// A MIDI sequence finished playing.
+  SequenceFinished,
+  /// Headphones or a microphone were plugged in or unplugged.
+  HeadphoneChanged,
+}
+
+/// The optional debug label and tag given to a callback through its builder.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct CallbackMeta {
+  pub label: Option<&'static str>,
+  pub tag: Option<&'static str>,
+}
+
+/// Describes a closure registered in a `Callbacks` collection, as returned from
+/// `Callbacks::iter()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CallbackInfo {
+  /// The unique id of the callback.
+  pub id: CallbackId,
+  /// The kind of system event the callback is run for.
+  pub kind: CallbackKind,
+  /// The debug label given to the callback's builder with `label()`, if any.
+  pub label: Option<&'static str>,
+  /// The tag given to the callback's builder with `tag()`, if any.
+  pub tag: Option<&'static str>,
+}
+
+/// A closure held in a `Callbacks` collection, along with how it was registered.
+struct CallbackEntry<F: ?Sized> {
+  id: CallbackId,
+  meta: CallbackMeta,
+  f: Box<F>,
+}
+
 /// The key used for each set of callbacks held in a `Callbacks` collection.
 ///
 /// They key type would need to be passed to the C callback function in order to find the
 /// user-provided closure from the key.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum CallbackKey {
   SoundSourceCompletion(usize),
   MenuItem(usize),
@@ -39,6 +93,17 @@ enum CallbackArguments {
   SequenceFinished(usize),
   HeadphoneChanged(HeadphoneState),
 }
+impl CallbackKey {
+  fn kind(&self) -> CallbackKind {
+    match self {
+      CallbackKey::SoundSourceCompletion(_) => CallbackKind::SoundSourceCompletion,
+      CallbackKey::MenuItem(_) => CallbackKind::MenuItem,
+      CallbackKey::SequenceFinished(_) => CallbackKind::SequenceFinished,
+      CallbackKey::HeadphoneChanged => CallbackKind::HeadphoneChanged,
+    }
+  }
+}
+
 impl CallbackArguments {
   fn is_none(&self) -> bool {
     match self {
@@ -55,12 +120,13 @@ impl CallbackArguments {
 #[derive(Debug)]
 pub(crate) struct RegisteredCallback {
   cb_type: Option<CallbackKey>,
-  weak_removed: Weak<RefCell<Vec<CallbackKey>>>,
+  id: CallbackId,
+  weak_removed: Weak<RefCell<Vec<(CallbackKey, CallbackId)>>>,
 }
 impl Drop for RegisteredCallback {
   fn drop(&mut self) {
     if let Some(removed) = self.weak_removed.upgrade() {
-      removed.borrow_mut().push(self.cb_type.take().unwrap())
+      removed.borrow_mut().push((self.cb_type.take().unwrap(), self.id))
     }
   }
 }
@@ -68,12 +134,16 @@ impl Drop for RegisteredCallback {
 /// Provides an API to run a closure tied to a callback when the `SystemEventWatcher` reports a
 /// callback is ready to be run via `SystemEvent::Callback`. This type uses its type argument `T` to
 /// define the values that the caller will pass along to the closure when running it.
+///
+/// Each closure can be given a debug label and a tag through its `CallbackBuilder`. The registered
+/// closures can be listed with `iter()`, and a group of them, such as every callback of a UI or
+/// audio subsystem, can be unregistered together with `unregister_tag()`.
 pub struct Callbacks<T> {
-  sound_source_completion_callbacks: BTreeMap<usize, Box<dyn Fn(T)>>,
-  menu_item_callbacks: BTreeMap<usize, Box<dyn Fn(T)>>,
-  sequence_finished_callbacks: BTreeMap<usize, Box<dyn Fn(T)>>,
-  headphone_changed_callback: Option<Box<dyn Fn(HeadphoneState, T)>>,
-  removed: Rc<RefCell<Vec<CallbackKey>>>,
+  sound_source_completion_callbacks: BTreeMap<usize, CallbackEntry<dyn Fn(T)>>,
+  menu_item_callbacks: BTreeMap<usize, CallbackEntry<dyn Fn(T)>>,
+  sequence_finished_callbacks: BTreeMap<usize, CallbackEntry<dyn Fn(T)>>,
+  headphone_changed_callback: Option<CallbackEntry<dyn Fn(HeadphoneState, T)>>,
+  removed: Rc<RefCell<Vec<(CallbackKey, CallbackId)>>>,
 }
 impl<T> Callbacks<T> {
   /// Construct a container for callbacks that will be passed `T` when they are run.
@@ -88,22 +158,107 @@ impl<T> Callbacks<T> {
   }
 
   fn gc(&mut self) {
-    for r in core::mem::take(&mut self.removed).borrow().iter() {
-      match r {
-        CallbackKey::SoundSourceCompletion(key) => {
-          self.sound_source_completion_callbacks.remove(key);
-        }
-        CallbackKey::MenuItem(key) => {
-          self.menu_item_callbacks.remove(key);
-        }
-        CallbackKey::SequenceFinished(key) => {
-          self.sequence_finished_callbacks.remove(key);
-        }
-        CallbackKey::HeadphoneChanged => {
+    let removed = core::mem::take(&mut *self.removed.borrow_mut());
+    for (key, id) in removed {
+      self.remove(key, id);
+    }
+  }
+
+  /// Removes the closure for `key` if it has the given `id`, as the closure may have already been
+  /// unregistered and the key reused for another closure.
+  fn remove(&mut self, key: CallbackKey, id: CallbackId) {
+    let matches = |entry_id: CallbackId| entry_id == id;
+    let (map, key) = match key {
+      CallbackKey::SoundSourceCompletion(key) => (&mut self.sound_source_completion_callbacks, key),
+      CallbackKey::MenuItem(key) => (&mut self.menu_item_callbacks, key),
+      CallbackKey::SequenceFinished(key) => (&mut self.sequence_finished_callbacks, key),
+      CallbackKey::HeadphoneChanged => {
+        if self.headphone_changed_callback.as_ref().is_some_and(|e| matches(e.id)) {
           self.headphone_changed_callback = None;
         }
-      };
+        return;
+      }
+    };
+    if map.get(&key).is_some_and(|e| matches(e.id)) {
+      map.remove(&key);
+    }
+  }
+
+  /// Returns every registered callback's key, along with its id and metadata.
+  fn entries(&self) -> impl Iterator<Item = (CallbackKey, CallbackId, CallbackMeta)> + '_ {
+    let sound = self
+      .sound_source_completion_callbacks
+      .iter()
+      .map(|(k, e)| (CallbackKey::SoundSourceCompletion(*k), e.id, e.meta));
+    let menu =
+      self.menu_item_callbacks.iter().map(|(k, e)| (CallbackKey::MenuItem(*k), e.id, e.meta));
+    let sequence = self
+      .sequence_finished_callbacks
+      .iter()
+      .map(|(k, e)| (CallbackKey::SequenceFinished(*k), e.id, e.meta));
+    let headphone =
+      self.headphone_changed_callback.iter().map(|e| (CallbackKey::HeadphoneChanged, e.id, e.meta));
+    sound.chain(menu).chain(sequence).chain(headphone)
+  }
+
+  /// Returns the number of closures registered in this collection.
+  pub fn len(&self) -> usize {
+    self.iter().count()
+  }
+  /// Returns whether no closures are registered in this collection.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns an iterator over the closures registered in this collection, with their ids, debug
+  /// labels and tags.
+  pub fn iter(&self) -> impl Iterator<Item = CallbackInfo> + '_ {
+    // Closures whose `RegisteredCallback` was dropped are not removed until the next call that can
+    // mutate the collection, so they are skipped here.
+    let removed: Vec<CallbackId> = self.removed.borrow().iter().map(|(_, id)| *id).collect();
+    self.entries().filter(move |(_, id, _)| !removed.contains(id)).map(|(key, id, meta)| {
+      CallbackInfo {
+        id,
+        kind: key.kind(),
+        label: meta.label,
+        tag: meta.tag,
+      }
+    })
+  }
+
+  /// Unregisters the closure with the given `id`, so that it will no longer be run. Returns whether
+  /// a closure with the `id` was registered.
+  ///
+  /// The system event that the closure was registered for may still occur, such as a menu item
+  /// being chosen, but `run()` will not find a closure to run for it, and will return false.
+  pub fn unregister(&mut self, id: CallbackId) -> bool {
+    self.gc();
+    let key = self.entries().find(|(_, entry_id, _)| *entry_id == id).map(|(key, _, _)| key);
+    match key {
+      Some(key) => {
+        self.remove(key, id);
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Unregisters every closure that was given the `tag` through its builder, so that they will no
+  /// longer be run. Returns the number of closures that were unregistered.
+  ///
+  /// The system events that the closures were registered for may still occur, but `run()` will not
+  /// find a closure to run for them, and will return false.
+  pub fn unregister_tag(&mut self, tag: &str) -> usize {
+    self.gc();
+    let keys: Vec<(CallbackKey, CallbackId)> = self
+      .entries()
+      .filter(|(_, _, meta)| meta.tag == Some(tag))
+      .map(|(key, id, _)| (key, id))
+      .collect();
+    for (key, id) in &keys {
+      self.remove(*key, *id);
     }
+    keys.len()
   }
 
   /// Attempt to run a callback, passing along a `T`.
@@ -121,19 +276,19 @@ impl<T> Callbacks<T> {
       CallbackArguments::None => false,
       CallbackArguments::SoundSourceCompletion(key) => {
         let cb = self.sound_source_completion_callbacks.get(key);
-        cb.and_then(|f| Some(f(t))).is_some()
+        cb.and_then(|e| Some((e.f)(t))).is_some()
       }
       CallbackArguments::MenuItem(key) => {
         let cb = self.menu_item_callbacks.get(key);
-        cb.and_then(|f| Some(f(t))).is_some()
+        cb.and_then(|e| Some((e.f)(t))).is_some()
       }
       CallbackArguments::SequenceFinished(key) => {
         let cb = self.sequence_finished_callbacks.get(key);
-        cb.and_then(|f| Some(f(t))).is_some()
+        cb.and_then(|e| Some((e.f)(t))).is_some()
       }
       CallbackArguments::HeadphoneChanged(state) => {
         let cb = self.headphone_changed_callback.as_ref();
-        cb.and_then(|f| Some(f(*state, t))).is_some()
+        cb.and_then(|e| Some((e.f)(*state, t))).is_some()
       }
     }
   }
@@ -145,15 +300,22 @@ impl<T> Callbacks<T> {
     &mut self,
     key: usize,
     cb: impl Fn(T) + 'static,
+    meta: CallbackMeta,
   ) -> (unsafe extern "C" fn(*mut CSoundSource), RegisteredCallback) {
-    let r = self.sound_source_completion_callbacks.insert(key, Box::new(cb));
+    self.gc();
+    let id = make_callback_id();
+    let r = self.sound_source_completion_callbacks.insert(
+      key,
+      CallbackEntry {
+        id,
+        meta,
+        f: Box::new(cb),
+      },
+    );
     assert!(r.is_none());
     (
       CCallbacks::on_sound_source_completion_callback,
-      RegisteredCallback {
-        cb_type: Some(CallbackKey::SoundSourceCompletion(key)),
-        weak_removed: Rc::downgrade(&self.removed),
-      },
+      self.registered(CallbackKey::SoundSourceCompletion(key), id),
     )
   }
 
@@ -162,15 +324,22 @@ impl<T> Callbacks<T> {
     &mut self,
     key: usize,
     cb: impl Fn(T) + 'static,
+    meta: CallbackMeta,
   ) -> (unsafe extern "C" fn(*mut c_void), RegisteredCallback) {
-    let r = self.menu_item_callbacks.insert(key, Box::new(cb));
+    self.gc();
+    let id = make_callback_id();
+    let r = self.menu_item_callbacks.insert(
+      key,
+      CallbackEntry {
+        id,
+        meta,
+        f: Box::new(cb),
+      },
+    );
     assert!(r.is_none());
     (
       CCallbacks::on_menu_item_callback,
-      RegisteredCallback {
-        cb_type: Some(CallbackKey::MenuItem(key)),
-        weak_removed: Rc::downgrade(&self.removed),
-      },
+      self.registered(CallbackKey::MenuItem(key), id),
     )
   }
 
@@ -179,18 +348,25 @@ impl<T> Callbacks<T> {
     &mut self,
     key: usize,
     cb: impl Fn(T) + 'static,
+    meta: CallbackMeta,
   ) -> (
     unsafe extern "C" fn(*mut CSoundSequence, *mut c_void),
     RegisteredCallback,
   ) {
-    let r = self.sound_source_completion_callbacks.insert(key, Box::new(cb));
+    self.gc();
+    let id = make_callback_id();
+    let r = self.sequence_finished_callbacks.insert(
+      key,
+      CallbackEntry {
+        id,
+        meta,
+        f: Box::new(cb),
+      },
+    );
     assert!(r.is_none());
     (
       CCallbacks::on_sequence_finished_callback,
-      RegisteredCallback {
-        cb_type: Some(CallbackKey::SequenceFinished(key)),
-        weak_removed: Rc::downgrade(&self.removed),
-      },
+      self.registered(CallbackKey::SequenceFinished(key), id),
     )
   }
 
@@ -198,17 +374,29 @@ impl<T> Callbacks<T> {
   pub(crate) fn add_headphone_change(
     &mut self,
     cb: impl Fn(HeadphoneState, T) + 'static,
+    meta: CallbackMeta,
   ) -> (unsafe extern "C" fn(i32, i32), RegisteredCallback) {
+    self.gc();
     assert!(self.headphone_changed_callback.is_none());
-    self.headphone_changed_callback = Some(Box::new(cb));
+    let id = make_callback_id();
+    self.headphone_changed_callback = Some(CallbackEntry {
+      id,
+      meta,
+      f: Box::new(cb),
+    });
     (
       CCallbacks::on_headphone_change_callback,
-      RegisteredCallback {
-        cb_type: Some(CallbackKey::HeadphoneChanged),
-        weak_removed: Rc::downgrade(&self.removed),
-      },
+      self.registered(CallbackKey::HeadphoneChanged, id),
     )
   }
+
+  fn registered(&self, key: CallbackKey, id: CallbackId) -> RegisteredCallback {
+    RegisteredCallback {
+      cb_type: Some(key),
+      id,
+      weak_removed: Rc::downgrade(&self.removed),
+    }
+  }
 }
 
 struct CCallbacks;
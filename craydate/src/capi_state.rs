@@ -1,12 +1,18 @@
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::rc::Rc;
+use alloc::vec::Vec;
 use core::cell::{Cell, RefCell};
 use core::ptr::NonNull;
 
 use crate::callbacks::RegisteredCallback;
 use crate::ctypes::*;
 use crate::executor::Executor;
-use crate::graphics::ContextStack;
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+use crate::files::DataFileWatches;
+use crate::graphics::{ContextStack, Font, SystemFont};
+use crate::null_terminated::ToNullTerminatedString;
+use crate::shutdown::ShutdownHook;
 use crate::system_event::{SystemEvent, SystemEventWatcherState};
 
 static mut GLOBAL_CAPI_STATE: Option<&'static CApiState> = None;
@@ -29,7 +35,17 @@ pub(crate) struct CApiState {
   pub stencil_generation: Cell<usize>,
   // Tracks how many times the font was set.
   pub font_generation: Cell<usize>,
+  // System fonts that have been loaded, which stay loaded for the rest of the program.
+  pub system_fonts: RefCell<BTreeMap<SystemFont, &'static Font>>,
+  // The font that text is drawn with when no other font is set, which is loaded at startup so that
+  // it can be set again without loading it. None if it failed to load.
+  pub default_font: Option<&'static Font>,
   pub system_event_watcher_state: RefCell<Rc<SystemEventWatcherState>>,
+  // Files in the Data folder being watched for changes, which is only possible in the simulator.
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  pub data_file_watches: RefCell<DataFileWatches>,
+  // Hooks registered with `on_shutdown()`, in the order they were registered.
+  pub shutdown_hooks: RefCell<Vec<ShutdownHook>>,
   // Tracks how many times the callback was set.
   pub headphone_change_generation: Cell<usize>,
   pub headphone_change_callback: RefCell<Option<RegisteredCallback>>,
@@ -37,8 +53,14 @@ pub(crate) struct CApiState {
 }
 impl CApiState {
   pub fn new(capi: &'static CPlaydateApi) -> CApiState {
+    let cgraphics = unsafe { &*capi.graphics };
+    let default_font = Self::load_default_font(cgraphics);
+    let mut system_fonts = BTreeMap::new();
+    if let Some(font) = default_font {
+      system_fonts.insert(SystemFont::DEFAULT, font);
+    }
     CApiState {
-      cgraphics: unsafe { &*capi.graphics },
+      cgraphics,
       csystem: unsafe { &*capi.system },
       cdisplay: unsafe { &*capi.display },
       cfile: unsafe { &*capi.file },
@@ -50,12 +72,28 @@ impl CApiState {
       stack: RefCell::new(ContextStack::new()),
       stencil_generation: Cell::new(0),
       font_generation: Cell::new(0),
+      system_fonts: RefCell::new(system_fonts),
+      default_font,
       system_event_watcher_state: RefCell::new(Rc::new(SystemEventWatcherState::new())),
+      #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+      data_file_watches: RefCell::new(DataFileWatches::new()),
+      shutdown_hooks: RefCell::new(Vec::new()),
       headphone_change_generation: Cell::new(0),
       headphone_change_callback: RefCell::new(None),
       headphone_change_func: RefCell::new(None),
     }
   }
+  fn load_default_font(cgraphics: &'static CGraphicsApi) -> Option<&'static Font> {
+    let mut out_err: *const u8 = core::ptr::null_mut();
+    let path = SystemFont::DEFAULT.path().to_null_terminated_utf8();
+    let font_ptr = unsafe { cgraphics.loadFont.unwrap()(path.as_ptr(), &mut out_err) };
+    if !out_err.is_null() || font_ptr.is_null() {
+      return None;
+    }
+    // Fonts are not freed when a `Font` is dropped, so leaking the `Font` only leaks the pointer.
+    Some(Box::leak(Box::new(Font::from_ptr(font_ptr))))
+  }
+
   pub fn set_instance(capi: &'static CApiState) {
     unsafe { GLOBAL_CAPI_STATE = Some(capi) };
   }
@@ -36,6 +36,15 @@ pub enum Error {
   DimensionsDoNotMatch,
   /// An error occured trying to read from a file to play it as audio.
   PlayFileError,
+  /// A save file has a version which can not be loaded, because it is newer than the current
+  /// version or because no migration from it is registered. Holds the version that can't be loaded.
+  UnsupportedSaveVersion(u32),
+  /// Text being imported as a save file is not valid, or its checksum does not match.
+  InvalidSaveExport,
+  /// Bytes being loaded as an `InputRecording` are not a valid recording.
+  InvalidInputRecording,
+  /// The data being encoded in a `QrCode` is too long to fit in the largest QR code.
+  QrCodeTooLong,
 }
 impl From<String> for Error {
   fn from(s: String) -> Self {
@@ -94,6 +103,10 @@ impl core::fmt::Debug for Error {
       Error::LoadMidiFileError => write!(f, "Error::LoadMidiFileError"),
       Error::DimensionsDoNotMatch => write!(f, "Error::DimensionsDoNotMatch"),
       Error::PlayFileError => write!(f, "Error::PlayFileError"),
+      Error::UnsupportedSaveVersion(v) => write!(f, "Error::UnsupportedSaveVersion({})", v),
+      Error::InvalidSaveExport => write!(f, "Error::InvalidSaveExport"),
+      Error::InvalidInputRecording => write!(f, "Error::InvalidInputRecording"),
+      Error::QrCodeTooLong => write!(f, "Error::QrCodeTooLong"),
       Error::String(e) => write!(f, "Error::String({:?})", e),
     }
   }
@@ -128,6 +141,10 @@ impl core::fmt::Display for Error {
       Error::LoadMidiFileError => write!(f, "MIDI file failed to load"),
       Error::DimensionsDoNotMatch => write!(f, "dimensions to not match"),
       Error::PlayFileError => write!(f, "failed to read file to play it as audio"),
+      Error::UnsupportedSaveVersion(v) => write!(f, "unable to load save file version {}", v),
+      Error::InvalidSaveExport => write!(f, "invalid save file export"),
+      Error::InvalidInputRecording => write!(f, "invalid input recording"),
+      Error::QrCodeTooLong => write!(f, "data is too long for a QR code"),
       Error::String(e) => e.fmt(f),
     }
   }
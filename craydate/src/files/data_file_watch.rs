@@ -0,0 +1,72 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::file::File;
+use super::file_path_stat::FilePathStat;
+
+/// How often, in milliseconds, the watched files are checked for changes. The file timestamps only
+/// have a resolution of seconds, so checking more often would not find changes any sooner.
+const POLL_INTERVAL_MS: u32 = 1000;
+
+#[derive(Debug)]
+struct WatchedDataFile {
+  path: String,
+  // The result of the last stat() of the path, or None if the path did not exist.
+  last_stat: Option<FilePathStat>,
+}
+
+/// The files being watched for changes with `File::watch_data_file()`. This is only used in the
+/// simulator, where the game's files can be edited on the host computer while it runs.
+#[derive(Debug)]
+pub(crate) struct DataFileWatches {
+  files: Vec<WatchedDataFile>,
+  // The time, in milliseconds, of the last check for changes.
+  last_poll_ms: Option<u32>,
+}
+impl DataFileWatches {
+  pub fn new() -> Self {
+    DataFileWatches {
+      files: Vec::new(),
+      last_poll_ms: None,
+    }
+  }
+
+  pub fn watch(&mut self, path: &str) {
+    if self.files.iter().any(|f| f.path == path) {
+      return;
+    }
+    self.files.push(WatchedDataFile {
+      path: String::from(path),
+      last_stat: File::new().stat(path).ok(),
+    });
+  }
+
+  pub fn unwatch(&mut self, path: &str) {
+    self.files.retain(|f| f.path != path)
+  }
+
+  /// Checks the watched files for changes, if enough time has passed since the last check at
+  /// `now_ms`, and returns the paths of any files which were modified, created or deleted.
+  pub fn poll_changes(&mut self, now_ms: u32) -> Vec<String> {
+    let mut changed = Vec::new();
+    if self.files.is_empty() {
+      return changed;
+    }
+    if let Some(last) = self.last_poll_ms {
+      if now_ms.wrapping_sub(last) < POLL_INTERVAL_MS {
+        return changed;
+      }
+    }
+    self.last_poll_ms = Some(now_ms);
+
+    let file = File::new();
+    for watched in &mut self.files {
+      let stat = file.stat(&watched.path).ok();
+      if stat != watched.last_stat {
+        watched.last_stat = stat;
+        changed.push(watched.path.clone());
+      }
+    }
+    changed
+  }
+}
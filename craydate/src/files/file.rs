@@ -2,11 +2,10 @@ use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::ffi::c_void;
-use core::ptr::NonNull;
 
 use super::file_path_stat::FilePathStat;
 use super::file_path_timestamp::FilePathTimestamp;
-use super::open_file::OpenFile;
+use super::open_file::{FileMode, OpenFile};
 use crate::capi_state::CApiState;
 use crate::ctypes::*;
 use crate::null_terminated::ToNullTerminatedString;
@@ -75,6 +74,29 @@ impl File {
     }
   }
 
+  /// Watches the file at `path` for changes while the game runs in the simulator.
+  ///
+  /// The file is checked about once per second, and a `SystemEvent::DataFileChanged` event with the
+  /// `path` is sent when it has been modified, created or deleted. This allows game data such as
+  /// tunables, dialogue or levels to be edited in the game's Data folder, and reloaded by the game
+  /// without restarting it.
+  ///
+  /// Files can not be changed while the game runs on the device, so this does nothing there.
+  pub fn watch_data_file(&self, path: &str) {
+    #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+    CApiState::get().data_file_watches.borrow_mut().watch(path);
+    #[cfg(all(target_arch = "arm", target_os = "none"))]
+    let _ = path;
+  }
+
+  /// Stops watching the file at `path` for changes, which was started by `watch_data_file()`.
+  pub fn unwatch_data_file(&self, path: &str) {
+    #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+    CApiState::get().data_file_watches.borrow_mut().unwatch(path);
+    #[cfg(all(target_arch = "arm", target_os = "none"))]
+    let _ = path;
+  }
+
   // Creates a folder at the given `path`.
   //
   // This function does not create intermediate folders. The path will be relocated relative to the
@@ -111,34 +133,23 @@ impl File {
     }
   }
 
+  /// Opens the file at `path` to read or write to it, depending on the `mode`.
+  ///
+  /// Files opened for reading are looked for in the game's data folder, and then in the game's
+  /// pdx. Files opened for writing are always in the game's data folder.
+  pub fn open(&self, path: &str, mode: FileMode) -> Result<OpenFile, FilePathError> {
+    OpenFile::open(path, mode)
+  }
+
   /// Read the entire contents of the file at `path`.
   ///
   /// The function will try to read from the game's data folder, and if it cannot find the file
   /// there, it will fallback to look in the game pdx.
   pub fn read_file(&self, path: &str) -> Result<Vec<u8>, FilePathError> {
-    // To open a file for reading in the simulator and on the hardware you currently have to set the mode to kFileRead|kFileReadData
-    let ptr = NonNull::new(unsafe {
-      Self::fns().open.unwrap()(
-        path.to_null_terminated_utf8().as_ptr(),
-        // TODO: Do we need to give the user the option to exclude the data folder or pdx?
-        craydate_sys::FileOptions::kFileReadData | craydate_sys::FileOptions::kFileRead,
-      )
-    });
-    match ptr {
-      None => Err(FilePathError {
-        path: String::from(path),
-        playdate: last_err(),
-      }),
-      Some(handle) => {
-        let mut f = OpenFile::new(handle);
-        let read_result = f.read_file();
-        let _close_result = f.close(); // We don't care if close() fails on a read.
-        read_result.ok_or_else(|| FilePathError {
-          path: String::from(path),
-          playdate: last_err(),
-        })
-      }
-    }
+    let mut f = self.open(path, FileMode::Read)?;
+    let read_result = f.read_to_end();
+    let _close_result = f.close(); // We don't care if close() fails on a read.
+    read_result
   }
 
   /// Write `contents` into the file at `path` in the game's data folder.
@@ -146,32 +157,24 @@ impl File {
   /// If a file exists at `path` it will be overwritten, otherwise a file will be created. If a
   /// folder exists at `path`, the write will fail.
   pub fn write_file(&self, path: &str, contents: &[u8]) -> Result<(), FilePathError> {
-    // To open a file for reading in the simulator and on the hardware you currently have to set the mode to kFileRead|kFileReadData
-    let ptr = NonNull::new(unsafe {
-      Self::fns().open.unwrap()(
-        path.to_null_terminated_utf8().as_ptr(),
-        craydate_sys::FileOptions::kFileWrite,
-      )
-    });
-    match ptr {
-      None => Err(FilePathError {
-        path: String::from(path),
-        playdate: last_err(),
-      }),
-      Some(handle) => {
-        let mut f = OpenFile::new(handle);
-        let write_result = f.write_file(contents);
-        // If close() fails on a write, we return an error as the file content may not be complete.
-        if f.close() && write_result {
-          Ok(())
-        } else {
-          Err(FilePathError {
-            path: String::from(path),
-            playdate: last_err(),
-          })
-        }
-      }
-    }
+    let mut f = self.open(path, FileMode::Write)?;
+    let write_result = f.write(contents);
+    // If close() fails on a write, we return an error as the file content may not be complete.
+    let close_result = f.close();
+    write_result.and(close_result)
+  }
+
+  /// Write `contents` to the end of the file at `path` in the game's data folder.
+  ///
+  /// If a file exists at `path` its contents are kept, otherwise a file will be created. This
+  /// allows adding to a file, such as a log, without rewriting all of it. If a folder exists at
+  /// `path`, the write will fail.
+  pub fn append_file(&self, path: &str, contents: &[u8]) -> Result<(), FilePathError> {
+    let mut f = self.open(path, FileMode::Append)?;
+    let write_result = f.write(contents);
+    // If close() fails on a write, we return an error as the file content may not be complete.
+    let close_result = f.close();
+    write_result.and(close_result)
   }
 
   /// Deletes the file or folder at `path` in the game's data folder.
@@ -1,8 +1,16 @@
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+mod data_file_watch;
 mod file;
 mod file_path_timestamp;
 mod open_file;
+mod save_export;
+mod save_file;
 mod file_path_stat;
 
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+pub(crate) use data_file_watch::DataFileWatches;
 pub use file::File;
 pub use file_path_timestamp::FilePathTimestamp;
-pub use file_path_stat::FilePathStat;
\ No newline at end of file
+pub use file_path_stat::FilePathStat;
+pub use open_file::{FileMode, OpenFile};
+pub use save_file::{SaveFile, SaveMigration};
\ No newline at end of file
@@ -1,86 +1,193 @@
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::ffi::c_void;
 use core::ptr::NonNull;
 
+use super::file::last_err;
 use crate::capi_state::CApiState;
 use crate::ctypes::*;
+use crate::error::FilePathError;
+use crate::null_terminated::ToNullTerminatedString;
 
-/// An open file which can be read from and written to.
+/// How a file is opened by `File::open()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FileMode {
+  /// Open the file for reading. The file is looked for in the game's data folder, and if it is not
+  /// found there, in the game's pdx.
+  Read,
+  /// Open the file in the game's data folder for writing. If the file exists, its contents are
+  /// removed, otherwise it is created.
+  Write,
+  /// Open the file in the game's data folder for writing at its end. If the file exists, its
+  /// contents are kept and all writes are added after them, otherwise it is created.
+  Append,
+}
+impl FileMode {
+  fn to_c(self) -> craydate_sys::FileOptions {
+    match self {
+      // To open a file for reading in the simulator and on the hardware you currently have to set
+      // the mode to kFileRead|kFileReadData.
+      FileMode::Read => {
+        craydate_sys::FileOptions::kFileReadData | craydate_sys::FileOptions::kFileRead
+      }
+      FileMode::Write => craydate_sys::FileOptions::kFileWrite,
+      FileMode::Append => craydate_sys::FileOptions::kFileAppend,
+    }
+  }
+}
+
+/// An open file which can be read from and written to, which is returned from `File::open()`.
 ///
-/// The close() function _must_ be called in order to destroy the `OpenFile` object. Dropping the
-/// OpenFile without calling close() will panic/abort.
+/// The file is closed when the `OpenFile` is dropped. To find out if closing the file was
+/// successful, which is important after writing to it, call `close()` instead.
 #[derive(Debug)]
-pub(super) struct OpenFile {
+pub struct OpenFile {
   handle: NonNull<COpenFile>,
+  path: String,
   closed: bool,
 }
 impl OpenFile {
-  pub(super) fn new(handle: NonNull<COpenFile>) -> Self {
-    OpenFile {
-      handle,
-      closed: false,
+  pub(crate) fn open(path: &str, mode: FileMode) -> Result<Self, FilePathError> {
+    let ptr =
+      unsafe { Self::fns().open.unwrap()(path.to_null_terminated_utf8().as_ptr(), mode.to_c()) };
+    match NonNull::new(ptr) {
+      Some(handle) => Ok(OpenFile {
+        handle,
+        path: String::from(path),
+        closed: false,
+      }),
+      None => Err(FilePathError {
+        path: String::from(path),
+        playdate: last_err(),
+      }),
     }
   }
 
-  /// Read the entire contents of the file.
-  pub fn read_file(&mut self) -> Option<Vec<u8>> {
+  /// The path the file was opened at.
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+
+  /// Reads from the file into `buf`, and returns the number of bytes read, which is 0 at the end of
+  /// the file.
+  pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, FilePathError> {
+    let result = unsafe {
+      Self::fns().read.unwrap()(
+        self.cptr_mut(),
+        buf.as_mut_ptr() as *mut c_void,
+        buf.len() as u32,
+      )
+    };
+    match result {
+      -1 => Err(self.err()),
+      read_bytes_count => Ok(read_bytes_count as usize),
+    }
+  }
+
+  /// Reads the rest of the contents of the file.
+  pub fn read_to_end(&mut self) -> Result<Vec<u8>, FilePathError> {
     let mut out = Vec::new();
-    const BUF_SIZE: u32 = 256;
-    let mut buf = [0; BUF_SIZE as usize];
+    const BUF_SIZE: usize = 256;
+    let mut buf = [0; BUF_SIZE];
     loop {
-      let result = unsafe {
-        Self::fns().read.unwrap()(self.cptr_mut(), buf.as_mut_ptr() as *mut c_void, BUF_SIZE)
-      };
-      let bytes = match result {
+      match self.read(&mut buf)? {
         // Reached the end of the file.
         0 => break,
-        // Return immediately on an error.
-        -1 => None?,
-        read_bytes_count => read_bytes_count as usize,
-      };
-      out.extend(buf[0..bytes].into_iter());
+        read_bytes_count => out.extend_from_slice(&buf[0..read_bytes_count]),
+      }
     }
-    Some(out)
+    Ok(out)
   }
 
-  /// Write the entire contents of the file, returns if the operation was successful.
-  pub fn write_file(&mut self, contents: &[u8]) -> bool {
-    // Note: This would be needed if we support other operations beyond read/write the whole
-    // file.
-    // Self::fns().seek.unwrap()(self.cptr(), 0, craydate_sys::SEEK_SET as i32);
+  /// Writes all of `contents` to the file.
+  ///
+  /// The write may be buffered, and can be sent to the file with `flush()` or `close()`.
+  pub fn write(&mut self, contents: &[u8]) -> Result<(), FilePathError> {
+    let mut written = 0;
+    while written < contents.len() {
+      let result = unsafe {
+        Self::fns().write.unwrap()(
+          self.cptr_mut(),
+          contents[written..].as_ptr() as *const c_void,
+          (contents.len() - written) as u32,
+        )
+      };
+      written += match result {
+        // Return immediately on an error. Nothing being written, such as when the disk is full, is
+        // also an error, as trying again would never finish.
+        -1 | 0 => return Err(self.err()),
+        written_bytes_count => written_bytes_count as usize,
+      };
+    }
+    Ok(())
+  }
 
-    const BUF_SIZE: usize = 256;
-    for buf in contents.chunks(BUF_SIZE) {
-      let mut written_from_buffer = 0;
-      loop {
-        let result = unsafe {
-          Self::fns().write.unwrap()(
-            self.cptr_mut().add(written_from_buffer),
-            buf.as_ptr() as *const c_void,
-            (buf.len() - written_from_buffer) as u32,
-          )
-        };
-        written_from_buffer += match result {
-          // Return immediately on an error.
-          -1 => return false,
-          written_bytes_count => written_bytes_count as usize,
-        };
-        if written_from_buffer == buf.len() {
-          break;
-        }
-      }
+  /// Sends any buffered writes to the file.
+  pub fn flush(&mut self) -> Result<(), FilePathError> {
+    match unsafe { Self::fns().flush.unwrap()(self.cptr_mut()) } {
+      -1 => Err(self.err()),
+      _ => Ok(()),
     }
-    true
   }
 
-  /// Close the file. This function _must_ be called in order to destroy the `OpenFile` object.
+  /// Returns the length of the file in bytes, including anything written to it so far.
   ///
-  /// Dropping the OpenFile without calling close() will panic/abort.
-  #[must_use]
-  pub fn close(mut self) -> bool {
+  /// This moves to the end of the file to find its length, and then back again, so the position in
+  /// the file that will be read or written next is not changed.
+  pub fn len(&mut self) -> Result<usize, FilePathError> {
+    let position = self.tell()?;
+    self.seek(0, craydate_sys::SEEK_END)?;
+    let len = self.tell();
+    self.seek(position as i32, craydate_sys::SEEK_SET)?;
+    len
+  }
+  /// Returns whether the file is empty.
+  pub fn is_empty(&mut self) -> Result<bool, FilePathError> {
+    Ok(self.len()? == 0)
+  }
+
+  /// Removes the contents of the file, so that it is empty, and returns it open for writing.
+  ///
+  /// The Playdate can only empty a file by opening it again, so any buffered writes are flushed,
+  /// then the file is closed and reopened in `FileMode::Write`. If the file was opened for reading,
+  /// it can no longer be read from. If an error occurs, the file is closed.
+  pub fn truncate(mut self) -> Result<OpenFile, FilePathError> {
+    // The file is closed before reopening it, so that nothing buffered in the old handle can be
+    // written into the emptied file.
+    self.flush()?;
+    let path = self.path.clone();
+    self.close()?;
+    OpenFile::open(&path, FileMode::Write)
+  }
+
+  /// Closes the file, and returns whether it was successful. When writing to the file, an error
+  /// here means that the contents of the file may not be complete.
+  pub fn close(mut self) -> Result<(), FilePathError> {
     self.closed = true;
     let result = unsafe { Self::fns().close.unwrap()(self.cptr_mut()) };
-    result == 0
+    match result {
+      0 => Ok(()),
+      _ => Err(self.err()),
+    }
+  }
+
+  fn tell(&mut self) -> Result<usize, FilePathError> {
+    match unsafe { Self::fns().tell.unwrap()(self.cptr_mut()) } {
+      -1 => Err(self.err()),
+      position => Ok(position as usize),
+    }
+  }
+  pub(crate) fn seek(&mut self, position: i32, whence: u32) -> Result<(), FilePathError> {
+    match unsafe { Self::fns().seek.unwrap()(self.cptr_mut(), position, whence as i32) } {
+      -1 => Err(self.err()),
+      _ => Ok(()),
+    }
+  }
+  fn err(&self) -> FilePathError {
+    FilePathError {
+      path: self.path.clone(),
+      playdate: last_err(),
+    }
   }
 
   pub(crate) fn cptr_mut(&mut self) -> *mut COpenFile {
@@ -93,8 +200,7 @@ impl OpenFile {
 impl Drop for OpenFile {
   fn drop(&mut self) {
     if !self.closed {
-      crate::log::log("ERROR: OpenFile dropped without calling close()");
-      assert!(self.closed);
+      unsafe { Self::fns().close.unwrap()(self.cptr_mut()) };
     }
   }
 }
@@ -0,0 +1,142 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::file::File;
+use super::save_file::SaveFile;
+use crate::error::Error;
+
+/// The Crockford base32 alphabet, which leaves out I, L, O and U so that text is easy to read back
+/// and type in by hand.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// The number of characters between each `-` in exported text.
+const GROUP_SIZE: usize = 5;
+
+impl SaveFile {
+  /// Exports the save file as text, so that a player can move their progress to another device.
+  ///
+  /// The text is the save file, including its version, encoded in base32 with a checksum at the end.
+  /// It is grouped into blocks of 5 characters separated by `-` to make it easier to copy by hand,
+  /// and can be shown on screen or written to a file with `export_to_file()`, which the player can
+  /// copy off the device when it is connected to a computer in data disk mode. It is loaded back
+  /// with `import_text()`.
+  pub fn export_text(&self, file: &File) -> Result<String, Error> {
+    let mut bytes = file.read_file(self.path())?;
+    bytes.extend_from_slice(&crc32(&bytes).to_le_bytes());
+    Ok(encode(&bytes))
+  }
+  /// Exports the save file as text with `export_text()`, and writes the text to the file at `path`
+  /// in the game's data folder.
+  pub fn export_to_file(&self, file: &File, path: &str) -> Result<(), Error> {
+    let text = self.export_text(file)?;
+    file.write_file(path, text.as_bytes())?;
+    Ok(())
+  }
+
+  /// Replaces the save file with one exported by `export_text()`.
+  ///
+  /// The text is checked before anything is written: letters may be in any case, the `-`
+  /// separators and whitespace are ignored, and the commonly confused letters O, I and L are read
+  /// as the digits they resemble. If the checksum does not match, `Error::InvalidSaveExport` is
+  /// returned. If the exported save is from a newer version than the current one,
+  /// `Error::UnsupportedSaveVersion` is returned. An older save is imported as it is, and will be
+  /// migrated when it is next loaded.
+  ///
+  /// The existing save file, if any, is kept at `backup_path()`. The imported save is written to a
+  /// temporary file and moved over the save file, as in `save()`, so a failed write does not leave a
+  /// partial save file behind.
+  pub fn import_text(&self, file: &File, text: &str) -> Result<(), Error> {
+    let bytes = decode(text).ok_or(Error::InvalidSaveExport)?;
+    if bytes.len() < 4 {
+      return Err(Error::InvalidSaveExport);
+    }
+    let (contents, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(contents).to_le_bytes() != checksum {
+      return Err(Error::InvalidSaveExport);
+    }
+    let (version, _) = SaveFile::parse(contents);
+    if version > self.version() {
+      return Err(Error::UnsupportedSaveVersion(version));
+    }
+
+    if let Ok(existing) = file.read_file(self.path()) {
+      file.write_file(&self.backup_path(), &existing)?;
+    }
+    self.replace(file, contents)
+  }
+  /// Reads text from the file at `path`, such as one the player copied onto the device, and imports
+  /// it with `import_text()`.
+  pub fn import_from_file(&self, file: &File, path: &str) -> Result<(), Error> {
+    let bytes = file.read_file(path)?;
+    let text = core::str::from_utf8(&bytes).map_err(|_| Error::InvalidSaveExport)?;
+    self.import_text(file, text)
+  }
+}
+
+/// Encodes `bytes` as base32 text, in groups of `GROUP_SIZE` characters.
+fn encode(bytes: &[u8]) -> String {
+  let mut text = String::new();
+  let mut count = 0;
+  let mut push = |text: &mut String, value: u32| {
+    if count > 0 && count % GROUP_SIZE == 0 {
+      text.push('-');
+    }
+    text.push(ALPHABET[value as usize & 31] as char);
+    count += 1;
+  };
+
+  // Each character holds 5 bits, taken from the most significant end of the bytes.
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for b in bytes {
+    buffer = (buffer << 8) | *b as u32;
+    bits += 8;
+    while bits >= 5 {
+      bits -= 5;
+      push(&mut text, buffer >> bits);
+    }
+  }
+  if bits > 0 {
+    push(&mut text, buffer << (5 - bits));
+  }
+  text
+}
+
+/// Decodes base32 text made by `encode()`, returning None if it contains invalid characters.
+fn decode(text: &str) -> Option<Vec<u8>> {
+  let mut bytes = Vec::new();
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for c in text.chars() {
+    let c = match c.to_ascii_uppercase() {
+      '-' | ' ' | '\t' | '\r' | '\n' => continue,
+      'O' => '0',
+      'I' | 'L' => '1',
+      c => c,
+    };
+    let value = ALPHABET.iter().position(|a| *a as char == c)? as u32;
+    buffer = (buffer << 5) | value;
+    bits += 5;
+    if bits >= 8 {
+      bits -= 8;
+      bytes.push((buffer >> bits) as u8);
+    }
+  }
+  // Any bits left over are the padding from the last character, which must be zero.
+  if buffer & ((1 << bits) - 1) != 0 {
+    return None;
+  }
+  Some(bytes)
+}
+
+/// Computes the CRC-32 checksum of `bytes`, as used by zip and png.
+fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = !0u32;
+  for b in bytes {
+    crc ^= *b as u32;
+    for _ in 0..8 {
+      let mask = (crc & 1).wrapping_neg();
+      crc = (crc >> 1) ^ (0xEDB88320 & mask);
+    }
+  }
+  !crc
+}
@@ -0,0 +1,149 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::file::File;
+use crate::error::Error;
+
+/// Written at the start of every save file, to tell versioned save files apart from files written
+/// before the game used `SaveFile`.
+const MAGIC: [u8; 4] = *b"CDSV";
+/// The size of the magic bytes followed by the little-endian `u32` version.
+const HEADER_SIZE: usize = 8;
+
+/// A function that converts the contents of a save file from one version to the next.
+pub type SaveMigration = fn(&[u8]) -> Result<Vec<u8>, Error>;
+
+/// A versioned save file, which is migrated to the current version when it is loaded.
+///
+/// Save files are written with a small header holding the version of the game's save format. When
+/// the save format changes, the game increases its current version and registers a migration with
+/// `add_migration()` that converts the contents from the previous version. When an older save file
+/// is loaded, each migration from its version up to the current version is run in order, and the
+/// migrated contents are written back to the file. The file as it was before migrating is kept
+/// alongside it with a `.bak` suffix, in case a migration has a bug.
+///
+/// Files that were written without a header, such as by a version of the game from before it used
+/// `SaveFile`, are treated as version 0.
+///
+/// # Example
+/// ```
+/// fn migrate_v1_to_v2(v1: &[u8]) -> Result<Vec<u8>, Error> {
+///   // Version 2 added a u32 high score after the version 1 data.
+///   let mut v2 = v1.to_vec();
+///   v2.extend_from_slice(&0u32.to_le_bytes());
+///   Ok(v2)
+/// }
+///
+/// let mut save = SaveFile::new("save.dat", 2);
+/// save.add_migration(1, migrate_v1_to_v2);
+/// let contents = save.load(&api.file)?;
+/// ```
+#[derive(Debug)]
+pub struct SaveFile {
+  path: String,
+  version: u32,
+  migrations: BTreeMap<u32, SaveMigration>,
+}
+impl SaveFile {
+  /// Constructs a `SaveFile` for the file at `path` in the game's data folder, where `version` is
+  /// the current version of the game's save format.
+  pub fn new(path: &str, version: u32) -> Self {
+    SaveFile {
+      path: String::from(path),
+      version,
+      migrations: BTreeMap::new(),
+    }
+  }
+
+  /// Registers the `migrate` function, which converts the contents of a save file from
+  /// `from_version` to `from_version + 1`.
+  ///
+  /// Registering a second migration for the same `from_version` replaces the first.
+  pub fn add_migration(&mut self, from_version: u32, migrate: SaveMigration) -> &mut Self {
+    assert!(from_version < self.version);
+    self.migrations.insert(from_version, migrate);
+    self
+  }
+
+  /// The current version of the save format, which `save()` writes.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+  /// The path of the save file in the game's data folder.
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+  /// The path where the save file is backed up before it is migrated.
+  pub fn backup_path(&self) -> String {
+    format!("{}.bak", self.path)
+  }
+
+  /// Writes `contents` to the save file, marked with the current version.
+  ///
+  /// The contents are written to a temporary file first, and then moved over the save file, so that
+  /// a failed write does not leave a partial save file behind.
+  pub fn save(&self, file: &File, contents: &[u8]) -> Result<(), Error> {
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + contents.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&self.version.to_le_bytes());
+    bytes.extend_from_slice(contents);
+    self.replace(file, &bytes)
+  }
+  /// Replaces the save file with `bytes`, which include the header, by writing them to a temporary
+  /// file and moving it over the save file.
+  pub(super) fn replace(&self, file: &File, bytes: &[u8]) -> Result<(), Error> {
+    let temp_path = format!("{}.tmp", self.path);
+    file.write_file(&temp_path, bytes)?;
+    file.rename(&temp_path, &self.path)?;
+    Ok(())
+  }
+
+  /// Reads the contents of the save file, migrating them to the current version if needed.
+  ///
+  /// If the file was migrated, the original file is written to `backup_path()` and the migrated
+  /// contents are saved in its place, before they are returned.
+  ///
+  /// Returns `Error::UnsupportedSaveVersion` if the file is from a newer version than the current
+  /// one, or if there is no migration registered for one of the versions it needs to pass through.
+  /// Any error returned from a migration is returned as well, and the file is left unchanged.
+  pub fn load(&self, file: &File) -> Result<Vec<u8>, Error> {
+    let bytes = file.read_file(&self.path)?;
+    let (mut version, contents) = Self::parse(&bytes);
+    if version == self.version {
+      return Ok(contents.to_vec());
+    }
+    if version > self.version {
+      return Err(Error::UnsupportedSaveVersion(version));
+    }
+
+    // Find every migration before running any, so nothing is changed if one is missing.
+    let mut steps = Vec::new();
+    while version < self.version {
+      match self.migrations.get(&version) {
+        Some(migrate) => steps.push(*migrate),
+        None => return Err(Error::UnsupportedSaveVersion(version)),
+      }
+      version += 1;
+    }
+    let mut migrated = contents.to_vec();
+    for migrate in steps {
+      migrated = migrate(&migrated)?;
+    }
+
+    file.write_file(&self.backup_path(), &bytes)?;
+    self.save(file, &migrated)?;
+    Ok(migrated)
+  }
+
+  /// Splits the bytes of a save file into its version and contents.
+  pub(super) fn parse(bytes: &[u8]) -> (u32, &[u8]) {
+    if bytes.len() >= HEADER_SIZE && bytes[..4] == MAGIC {
+      let version = u32::from_le_bytes(bytes[4..HEADER_SIZE].try_into().unwrap());
+      (version, &bytes[HEADER_SIZE..])
+    } else {
+      (0, bytes)
+    }
+  }
+}
@@ -2,7 +2,7 @@ use super::font::Font;
 use crate::capi_state::CApiState;
 
 /// A sentinel that marks a font as the currently active font. Destroying this object will
-/// unset the font as current.
+/// unset the font as current, and text will be drawn with the default system font again.
 pub struct ActiveFont<'a> {
   generation: usize,
   font: &'a Font,
@@ -29,8 +29,15 @@ impl Drop for ActiveFont<'_> {
   fn drop(&mut self) {
     // Use a generation tag to avoid unsetting the font if another font was set before this
     // object was dropped.
+    //
+    // Setting a null font would leave no font to draw text with, so restore the system's default
+    // font which is used before any font is set. It is loaded at startup, and if that failed then
+    // the font is left as it is.
     if self.generation == CApiState::get().font_generation.get() {
-      unsafe { Self::fns().setFont.unwrap()(core::ptr::null_mut()) }
+      if let Some(default_font) = CApiState::get().default_font {
+        // setFont() takes a mutable pointer but does not write to the data.
+        unsafe { Self::fns().setFont.unwrap()(default_font.cptr() as *mut _) }
+      }
     }
   }
 }
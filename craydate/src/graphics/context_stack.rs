@@ -2,6 +2,7 @@ use alloc::{collections::BTreeMap, vec::Vec};
 
 use super::bitmap::Bitmap;
 use crate::capi_state::CApiState;
+use crate::ctypes::*;
 
 #[derive(Debug)]
 struct StackBitmap {
@@ -83,6 +84,16 @@ impl ContextStack {
       }
     })
   }
+  /// The bitmap that drawing commands are currently being applied to, or null for the
+  /// framebuffer.
+  pub fn current_target(&self) -> *mut CBitmap {
+    match self.stack.last() {
+      // pushContext() takes a mutable pointer but does not change the data inside it.
+      Some(Some(stack_b)) => stack_b.bitmap.cptr() as *mut _,
+      Some(None) | None => core::ptr::null_mut(),
+    }
+  }
+
   pub fn take_bitmap(&mut self, id: ContextStackId) -> Option<Bitmap> {
     let r = self.holding.remove(&id.id).and_then(|held| held.bitmap);
     // We can forget the ContextStackId as no id can refer to the bitmap once it's removed, and we
@@ -0,0 +1,218 @@
+use alloc::vec::Vec;
+
+use super::bitmap::BitmapRef;
+use super::color::{Color, PixelColor};
+use super::graphics::Graphics;
+
+/// The directions that a contour can step in, from one pixel corner to the next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Step {
+  North,
+  East,
+  South,
+  West,
+}
+impl Step {
+  fn index(self) -> u8 {
+    match self {
+      Step::North => 0,
+      Step::East => 1,
+      Step::South => 2,
+      Step::West => 3,
+    }
+  }
+  fn offset(self) -> (i32, i32) {
+    match self {
+      Step::North => (0, -1),
+      Step::East => (1, 0),
+      Step::South => (0, 1),
+      Step::West => (-1, 0),
+    }
+  }
+}
+
+/// A closed outline around a solid area of a `Bitmap` or a grid of cells, traced with the marching
+/// squares algorithm.
+///
+/// The points are at the corners of pixels (or cells), so the outline runs along the edges of the
+/// solid pixels and a single solid pixel at `(x, y)` produces the outline `(x, y)`, `(x, y + 1)`,
+/// `(x + 1, y + 1)`, `(x + 1, y)`. Only corners where the outline changes direction are included,
+/// and the last point connects back to the first.
+///
+/// Outlines are traced with the solid area on their left, so the outside edge of a solid area
+/// winds counter-clockwise as seen on the screen, and the edge of a hole inside a solid area winds
+/// clockwise. Solid pixels that touch only at a corner are given separate outlines.
+///
+/// The points can be drawn with `draw()`, filled with `Graphics::fill_polygon()`, or used as a
+/// collision polygon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contour {
+  points: Vec<euclid::default::Point2D<i32>>,
+}
+impl Contour {
+  /// Traces the outlines of all areas of pixels in the `bitmap` that have the `solid` color.
+  ///
+  /// To trace the opaque areas of a bitmap, trace its mask from `BitmapRef::mask_bitmap()` with a
+  /// `solid` color of `PixelColor::WHITE`.
+  pub fn trace_bitmap(bitmap: &BitmapRef, solid: PixelColor) -> Vec<Contour> {
+    let data = bitmap.data();
+    let pixels = bitmap.as_pixels();
+    Self::trace_grid(
+      euclid::rect(0, 0, data.width(), data.height()),
+      |x, y| pixels.get(x as usize, y as usize) == solid,
+    )
+  }
+
+  /// Traces the outlines of all areas of solid cells inside the `rect` of a grid.
+  ///
+  /// The `is_solid` function is called with the (`x`, `y`) position of cells inside `rect`, and
+  /// should return whether the cell is solid. Cells outside of `rect` are treated as empty, which
+  /// allows re-tracing just the area around a change, such as when destroying part of the terrain.
+  /// The points of the returned outlines are in the same coordinates as the cells.
+  pub fn trace_grid<F: Fn(i32, i32) -> bool>(
+    rect: euclid::default::Rect<i32>,
+    is_solid: F,
+  ) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    if rect.is_empty() {
+      return contours;
+    }
+
+    let (left, top) = (rect.origin.x, rect.origin.y);
+    let (width, height) = (rect.size.width, rect.size.height);
+    let solid = |x: i32, y: i32| {
+      x >= left && y >= top && x < left + width && y < top + height && is_solid(x, y)
+    };
+    // The marching squares case for the pixel corner at (`x`, `y`), built from the 4 pixels that
+    // share the corner.
+    let case = |x: i32, y: i32| {
+      (solid(x - 1, y - 1) as u8)
+        | (solid(x, y - 1) as u8) << 1
+        | (solid(x - 1, y) as u8) << 2
+        | (solid(x, y) as u8) << 3
+    };
+
+    // Tracks which steps have already been taken out of each corner, as a bit per `Step`.
+    let corners_wide = (width + 1) as usize;
+    let mut visited = alloc::vec![0u8; corners_wide * (height + 1) as usize];
+    let visited_index = |x: i32, y: i32| (y - top) as usize * corners_wide + (x - left) as usize;
+
+    for y in top..=top + height {
+      for x in left..=left + width {
+        let c = case(x, y);
+        // A corner has 2 ways out of it when it joins 2 diagonal pixels, otherwise at most 1.
+        let starts = match c {
+          0 | 15 => [None, None],
+          6 => [Some(Step::West), Some(Step::East)],
+          9 => [Some(Step::North), Some(Step::South)],
+          _ => [Some(next_step(c, Step::North)), None],
+        };
+        for start in starts.into_iter().flatten() {
+          if visited[visited_index(x, y)] & (1 << start.index()) != 0 {
+            continue;
+          }
+
+          let mut points = Vec::new();
+          let (mut cx, mut cy) = (x, y);
+          let mut step = start;
+          let mut prev_step = None;
+          loop {
+            visited[visited_index(cx, cy)] |= 1 << step.index();
+            if prev_step != Some(step) {
+              points.push(euclid::point2(cx, cy));
+            }
+            let (dx, dy) = step.offset();
+            cx += dx;
+            cy += dy;
+            prev_step = Some(step);
+            step = next_step(case(cx, cy), step);
+            if (cx, cy) == (x, y) && step == start {
+              break;
+            }
+          }
+          // The walk may end moving in the same direction it started, which makes the first point
+          // a middle point of a straight edge.
+          if prev_step == Some(start) && points.len() > 1 {
+            points.remove(0);
+          }
+          contours.push(Contour { points });
+        }
+      }
+    }
+    contours
+  }
+
+  /// The corners of the outline, in order. The last point connects back to the first.
+  pub fn points(&self) -> &[euclid::default::Point2D<i32>] {
+    &self.points
+  }
+  /// Consumes the `Contour` and returns its points.
+  pub fn into_points(self) -> Vec<euclid::default::Point2D<i32>> {
+    self.points
+  }
+
+  /// Returns whether the outline is the edge of a hole inside a solid area, rather than the outside
+  /// edge of a solid area.
+  pub fn is_hole(&self) -> bool {
+    self.twice_signed_area() > 0
+  }
+  /// The area inside the outline, in pixels (or cells).
+  pub fn area(&self) -> i32 {
+    self.twice_signed_area().abs() / 2
+  }
+
+  fn twice_signed_area(&self) -> i32 {
+    let n = self.points.len();
+    (0..n)
+      .map(|i| {
+        let (a, b) = (self.points[i], self.points[(i + 1) % n]);
+        a.x * b.y - b.x * a.y
+      })
+      .sum()
+  }
+
+  /// Draws the outline as a series of lines with a stroke width of `line_width`.
+  pub fn draw<'a>(&self, graphics: &mut Graphics, line_width: i32, color: Color<'a>) {
+    let n = self.points.len();
+    for i in 0..n {
+      let (a, b) = (self.points[i], self.points[(i + 1) % n]);
+      // Color is not Copy, but it only holds a SolidColor or a shared reference to a Pattern.
+      let color = match &color {
+        Color::Solid(solid) => Color::Solid(*solid),
+        Color::Pattern(pattern) => Color::Pattern(pattern),
+      };
+      graphics.draw_line(a, b, line_width, color);
+    }
+  }
+}
+
+/// Returns the direction to step out of a pixel corner, given its marching squares `case` and the
+/// `prev` step that arrived at the corner.
+///
+/// The steps keep solid pixels on the left side of the outline. The bits of the `case` are the
+/// pixels around the corner: 1 is up-left, 2 is up-right, 4 is down-left, and 8 is down-right.
+fn next_step(case: u8, prev: Step) -> Step {
+  match case {
+    1 | 5 | 13 => Step::North,
+    2 | 3 | 7 => Step::East,
+    4 | 12 | 14 => Step::West,
+    8 | 10 | 11 => Step::South,
+    // Up-right and down-left are solid. Keep turning around the same pixel.
+    6 => {
+      if prev == Step::North {
+        Step::West
+      } else {
+        Step::East
+      }
+    }
+    // Up-left and down-right are solid. Keep turning around the same pixel.
+    9 => {
+      if prev == Step::East {
+        Step::North
+      } else {
+        Step::South
+      }
+    }
+    _ => unreachable!("no outline passes through a corner with case {}", case),
+  }
+}
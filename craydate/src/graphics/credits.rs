@@ -0,0 +1,214 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::color::Color;
+use super::font::Font;
+use super::graphics::Graphics;
+use super::large_text::LargeText;
+use crate::capi_state::CApiState;
+use crate::ctypes::*;
+use crate::inputs::Crank;
+use crate::time::TimeDelta;
+
+/// The number of pixels between the two columns of a two-column line.
+const COLUMN_GAP: i32 = 12;
+
+#[derive(Debug)]
+enum CreditsEntry {
+  Header(String),
+  Line(String),
+  Columns(String, String),
+  Space(i32),
+}
+
+/// A scrolling list of credits, such as is shown at the end of a game.
+///
+/// Credits are made of section headers, lines of text which are centered, and lines with two
+/// columns, such as a role and a name, which are aligned around the center. They scroll up from the
+/// bottom of their area at a steady speed, and the crank can be used to speed them up or scroll
+/// them back.
+///
+/// The scroll position is kept as a fraction, but each frame is drawn at a whole pixel, so the text
+/// does not shimmer as it would if it was drawn at a different sub-pixel offset each frame. A speed
+/// that is a multiple of the frame rate, such as 30 pixels per second at 30 frames per second,
+/// moves the same number of rows each frame, which looks the smoothest on the display.
+#[derive(Debug)]
+pub struct Credits<'a> {
+  body_font: &'a Font,
+  header: LargeText<'a>,
+  area: euclid::default::Rect<i32>,
+  entries: Vec<CreditsEntry>,
+  line_spacing: i32,
+  // The number of pixels the credits have scrolled up from the bottom of the area.
+  position: f32,
+  speed: f32,
+  crank_ratio: f32,
+}
+impl<'a> Credits<'a> {
+  /// Constructs empty credits that are drawn in `area`, with text in the `body_font` and section
+  /// headers in the `header_font`, drawn at `header_scale` times its size.
+  pub fn new(
+    body_font: &'a Font,
+    header_font: &'a Font,
+    header_scale: i32,
+    area: euclid::default::Rect<i32>,
+  ) -> Self {
+    Credits {
+      body_font,
+      header: LargeText::new(header_font, header_scale),
+      area,
+      entries: Vec::new(),
+      line_spacing: 2,
+      position: 0.0,
+      speed: 30.0,
+      crank_ratio: 1.0,
+    }
+  }
+
+  /// Adds a section header, which is centered in the header font.
+  pub fn add_header(&mut self, text: &str) -> &mut Self {
+    self.entries.push(CreditsEntry::Header(String::from(text)));
+    self
+  }
+  /// Adds a line of text, which is centered.
+  pub fn add_line(&mut self, text: &str) -> &mut Self {
+    self.entries.push(CreditsEntry::Line(String::from(text)));
+    self
+  }
+  /// Adds a line with two columns, where the `left` text ends just left of the center, and the
+  /// `right` text starts just right of it.
+  pub fn add_columns(&mut self, left: &str, right: &str) -> &mut Self {
+    self.entries.push(CreditsEntry::Columns(
+      String::from(left),
+      String::from(right),
+    ));
+    self
+  }
+  /// Adds empty space of `height` pixels.
+  pub fn add_space(&mut self, height: i32) -> &mut Self {
+    self.entries.push(CreditsEntry::Space(height));
+    self
+  }
+  /// Adds credits written as text, such as from a file in the game's pdx, one line at a time:
+  ///
+  /// * A line starting with `# ` is a section header.
+  /// * A line containing a `|` has two columns, split at the `|`.
+  /// * An empty line adds space the height of a line.
+  /// * Any other line is a line of text.
+  pub fn add_text(&mut self, text: &str) -> &mut Self {
+    for line in text.lines() {
+      let line = line.trim_end();
+      if let Some(header) = line.strip_prefix("# ") {
+        self.add_header(header);
+      } else if let Some((left, right)) = line.split_once('|') {
+        self.add_columns(left.trim(), right.trim());
+      } else if line.is_empty() {
+        self.add_space(self.body_font.font_height() as i32);
+      } else {
+        self.add_line(line);
+      }
+    }
+    self
+  }
+
+  /// Sets the number of pixels between each line. The default is 2.
+  pub fn set_line_spacing(&mut self, spacing: i32) {
+    self.line_spacing = spacing;
+  }
+  /// Sets the speed that the credits scroll, in pixels per second. The default is 30.
+  pub fn set_speed(&mut self, pixels_per_second: f32) {
+    self.speed = pixels_per_second;
+  }
+  /// Sets how many pixels the credits scroll for each degree the crank turns. The default is 1.
+  ///
+  /// Turning the crank clockwise scrolls the credits forward, and counter-clockwise scrolls them
+  /// back.
+  pub fn set_crank_ratio(&mut self, pixels_per_degree: f32) {
+    self.crank_ratio = pixels_per_degree;
+  }
+
+  /// The height of all the credits, in pixels.
+  pub fn total_height(&self) -> i32 {
+    self.entries.iter().map(|e| self.entry_height(e)).sum()
+  }
+  /// Whether the credits have scrolled all the way off the top of their area.
+  pub fn is_finished(&self) -> bool {
+    self.position >= (self.total_height() + self.area.height()) as f32
+  }
+  /// Moves the credits back to the start, below the bottom of their area.
+  pub fn restart(&mut self) {
+    self.position = 0.0;
+  }
+
+  /// Scrolls the credits for a frame that took `dt` time, with the `crank` from the frame's
+  /// `Inputs`.
+  pub fn update(&mut self, dt: TimeDelta, crank: &Crank) {
+    self.position += self.speed * dt.to_seconds();
+    if let Crank::Undocked { change, .. } = crank {
+      self.position += change * self.crank_ratio;
+    }
+    let end = (self.total_height() + self.area.height()) as f32;
+    self.position = self.position.clamp(0.0, end);
+  }
+
+  /// Draws the credits in their area, which is cleared to white first.
+  ///
+  /// The credits are drawn in a pushed drawing context, with the body font and a clip rect for
+  /// their area, so the game's font, clip rect and draw mode are unchanged afterward.
+  pub fn draw(&self, graphics: &mut Graphics) {
+    // Push the current target again, so that the credits are drawn where the game is drawing, and
+    // the game's drawing state is restored when it is popped.
+    let target = CApiState::get().stack.borrow().current_target();
+    unsafe { Graphics::fns().pushContext.unwrap()(target) };
+
+    let area = self.area;
+    graphics.fill_rect(area, Color::Solid(SolidColor::kColorWhite));
+    graphics.set_clip_rect(area);
+    // The font is set directly rather than through an `ActiveFont`, which would set the default
+    // font when dropped, and would stop an `ActiveFont` held by the game from unsetting its font.
+    //
+    // setFont() takes a mutable pointer but does not write to the data.
+    unsafe { Graphics::fns().setFont.unwrap()(self.body_font.cptr() as *mut _) };
+    let center = area.min_x() + area.width() / 2;
+
+    // Each frame is drawn at a whole pixel.
+    let mut y = area.max_y() - self.position as i32;
+    for entry in &self.entries {
+      let height = self.entry_height(entry);
+      // Skip entries outside the area, and stop once past the bottom of it.
+      if y >= area.max_y() {
+        break;
+      }
+      if y + height > area.min_y() {
+        match entry {
+          CreditsEntry::Header(text) => {
+            let width = self.header.measure_text_width(text);
+            self.header.draw(graphics, text, center - width / 2, y);
+          }
+          CreditsEntry::Line(text) => {
+            let width = self.body_font.measure_text_width(text, 0);
+            graphics.draw_text(text, center - width / 2, y);
+          }
+          CreditsEntry::Columns(left, right) => {
+            let width = self.body_font.measure_text_width(left, 0);
+            graphics.draw_text(left, center - COLUMN_GAP / 2 - width, y);
+            graphics.draw_text(right, center + COLUMN_GAP / 2, y);
+          }
+          CreditsEntry::Space(_) => (),
+        }
+      }
+      y += height;
+    }
+    unsafe { Graphics::fns().popContext.unwrap()() };
+  }
+
+  fn entry_height(&self, entry: &CreditsEntry) -> i32 {
+    match entry {
+      CreditsEntry::Header(_) => self.header.line_height() + self.line_spacing,
+      CreditsEntry::Line(_) | CreditsEntry::Columns(..) => {
+        self.body_font.font_height() as i32 + self.line_spacing
+      }
+      CreditsEntry::Space(height) => *height,
+    }
+  }
+}
@@ -0,0 +1,120 @@
+use super::color::Color;
+use super::font::Font;
+use super::graphics::Graphics;
+use crate::ctypes::*;
+
+/// The colors that can be drawn to a `DebugLayer`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DebugColor {
+  /// Drawn over the frame in the simulator's debug color, which is chosen in the simulator's
+  /// settings.
+  Highlight,
+  /// Transparent, which erases anything drawn to the debug layer before.
+  Clear,
+}
+impl DebugColor {
+  fn to_color(self) -> Color<'static> {
+    match self {
+      // The simulator shows the white pixels of its debug bitmap in the debug color, and leaves the
+      // black pixels transparent.
+      DebugColor::Highlight => Color::Solid(SolidColor::kColorWhite),
+      DebugColor::Clear => Color::Solid(SolidColor::kColorBlack),
+    }
+  }
+}
+
+/// Drawing to the simulator's debug layer, which is shown in color over the frame but is not part
+/// of the frame itself.
+///
+/// This is useful for drawing things like collision boxes while developing, as they can't be
+/// mistaken for the game's own drawing, and they don't change what is displayed on the device or in
+/// screenshots.
+///
+/// The debug layer only exists in the simulator. On the device, every drawing function does
+/// nothing, so debug drawing can be left in the game without affecting it.
+#[derive(Debug)]
+pub struct DebugLayer<'a> {
+  #[cfg_attr(all(target_arch = "arm", target_os = "none"), allow(dead_code))]
+  graphics: &'a mut Graphics,
+}
+impl<'a> DebugLayer<'a> {
+  pub(crate) fn new(graphics: &'a mut Graphics) -> Self {
+    DebugLayer { graphics }
+  }
+
+  /// Runs `f` with the debug bitmap as the drawing target.
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn draw<F: FnOnce(&mut Graphics)>(&mut self, f: F) {
+    let bitmap = unsafe { Graphics::fns().getDebugBitmap.unwrap()() };
+    if bitmap.is_null() {
+      return;
+    }
+    // This pushes and pops the debug bitmap around a single drawing command, so it does not need
+    // to be tracked in the ContextStack.
+    unsafe { Graphics::fns().pushContext.unwrap()(bitmap) };
+    f(self.graphics);
+    unsafe { Graphics::fns().popContext.unwrap()() };
+  }
+  #[cfg(all(target_arch = "arm", target_os = "none"))]
+  fn draw<F: FnOnce(&mut Graphics)>(&mut self, _f: F) {}
+
+  /// Erases everything drawn to the debug layer.
+  pub fn clear(&mut self) {
+    self.draw(|g| g.clear(DebugColor::Clear.to_color()))
+  }
+
+  /// Draws a single pixel at `point`.
+  pub fn draw_point(&mut self, point: euclid::default::Point2D<i32>, color: DebugColor) {
+    self.fill_rect(euclid::rect(point.x, point.y, 1, 1), color)
+  }
+  /// Draws a line from `p1` to `p2` with a stroke width of `line_width`.
+  pub fn draw_line(
+    &mut self,
+    p1: euclid::default::Point2D<i32>,
+    p2: euclid::default::Point2D<i32>,
+    line_width: i32,
+    color: DebugColor,
+  ) {
+    self.draw(|g| g.draw_line(p1, p2, line_width, color.to_color()))
+  }
+  /// Draws the outline of `rect`.
+  pub fn draw_rect(&mut self, rect: euclid::default::Rect<i32>, color: DebugColor) {
+    self.draw(|g| g.draw_rect(rect, color.to_color()))
+  }
+  /// Fills `rect`.
+  pub fn fill_rect(&mut self, rect: euclid::default::Rect<i32>, color: DebugColor) {
+    self.draw(|g| g.fill_rect(rect, color.to_color()))
+  }
+  /// Draws the outline of an ellipse inside `rect`, with a stroke width of `line_width`.
+  pub fn draw_elipse(
+    &mut self,
+    rect: euclid::default::Rect<i32>,
+    line_width: i32,
+    color: DebugColor,
+  ) {
+    self.draw(|g| g.draw_elipse(rect, line_width, 0.0, 0.0, color.to_color()))
+  }
+  /// Draws `text` with its upper-left corner at (`x`, `y`), in the current font.
+  ///
+  /// The text is always drawn in `DebugColor::Highlight`.
+  pub fn draw_text(&mut self, text: &str, x: i32, y: i32) {
+    // Text is drawn in black by default, which is transparent in the debug bitmap, so invert it.
+    self.draw(|g| {
+      g.set_draw_mode(BitmapDrawMode::kDrawModeFillWhite);
+      g.draw_text(text, x, y);
+      g.set_draw_mode(BitmapDrawMode::kDrawModeCopy);
+    })
+  }
+  /// Draws `text` with its upper-left corner at (`x`, `y`) in the `font`, without changing the
+  /// current font.
+  pub(crate) fn draw_text_in_font(&mut self, text: &str, font: &Font, x: i32, y: i32) {
+    self.draw(|g| {
+      // The font is set in the debug bitmap's pushed context, so the game's font is back when it is
+      // popped. setFont() takes a mutable pointer but does not write to the data.
+      unsafe { Graphics::fns().setFont.unwrap()(font.cptr() as *mut _) };
+      g.set_draw_mode(BitmapDrawMode::kDrawModeFillWhite);
+      g.draw_text(text, x, y);
+      g.set_draw_mode(BitmapDrawMode::kDrawModeCopy);
+    })
+  }
+}
@@ -0,0 +1,189 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::debug_layer::DebugColor;
+use super::graphics::Graphics;
+use crate::capi_state::CApiState;
+use crate::ctypes_enums::{LCD_COLUMNS, LCD_ROWBYTES, LCD_ROWS};
+use crate::system::System;
+
+/// How long, in milliseconds, flashes are counted over.
+const WINDOW_MS: u32 = 1000;
+/// How long, in milliseconds, the warning overlay is shown after flashing is found.
+const OVERLAY_MS: u32 = 1000;
+
+/// A change in brightness of a large area of the display between two frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Transition {
+  Brighter,
+  Darker,
+}
+
+/// Watches the frames shown on the display while developing a game, and warns when they flash more
+/// than photosensitivity guidelines allow.
+///
+/// This follows the general flash threshold of the WCAG guidelines: there should be no more than 3
+/// flashes in any one second. A flash is a pair of opposing changes in brightness, such as from
+/// dark to light and back again. A change counts when at least the `area_threshold` fraction of the
+/// display's pixels change in the same direction between frames. On a display of black and white
+/// pixels, every changed pixel is a full change in brightness.
+///
+/// Call `analyze()` once per frame. When too many flashes are found, a message is logged, and a
+/// warning is drawn on the simulator's `DebugLayer` for a second. The warning is drawn again each
+/// frame while it is shown, so `analyze()` should be called after anything else that draws to the
+/// debug layer, such as `Gizmos::flush()`, which could otherwise erase part of it.
+///
+/// The analyzer only runs in debug builds. In release builds `analyze()` does nothing, so it can be
+/// left in the game. Players can ask games to avoid flashing with the reduce flashing setting, which
+/// is given by `System::is_reduce_flashing_enabled()`.
+#[derive(Debug)]
+pub struct FlashAnalyzer {
+  area_threshold: f32,
+  max_flashes_per_second: u32,
+  // The pixels of the last frame analyzed, or empty if no frame has been analyzed yet.
+  previous: Vec<u8>,
+  // The time and direction of each transition in the last `WINDOW_MS`.
+  transitions: VecDeque<(u32, Transition)>,
+  // The last time too much flashing was found, which is None once the warning has been shown for
+  // `OVERLAY_MS` without more flashing.
+  last_flashing: Option<u32>,
+}
+impl FlashAnalyzer {
+  /// Constructs an analyzer with the WCAG limits: a change of a quarter of the display counts, and
+  /// up to 3 flashes per second are allowed.
+  pub fn new() -> Self {
+    FlashAnalyzer {
+      area_threshold: 0.25,
+      max_flashes_per_second: 3,
+      previous: Vec::new(),
+      transitions: VecDeque::new(),
+      last_flashing: None,
+    }
+  }
+
+  /// Sets the fraction of the display, between 0 and 1, that must change in brightness at once to
+  /// count as half of a flash.
+  pub fn set_area_threshold(&mut self, fraction: f32) {
+    self.area_threshold = fraction.clamp(0.0, 1.0);
+  }
+  /// Sets the number of flashes allowed in any one second before warning.
+  pub fn set_max_flashes_per_second(&mut self, flashes: u32) {
+    self.max_flashes_per_second = flashes;
+  }
+
+  /// Whether the frames analyzed in the last second flashed more than is allowed.
+  pub fn is_flashing_too_much(&self) -> bool {
+    self.last_flashing.is_some()
+  }
+
+  /// Compares the frame currently shown on the display to the one shown when this was last called,
+  /// and warns if the display has flashed too much in the last second.
+  ///
+  /// This should be called once each frame, after any other drawing to the `DebugLayer`, so that
+  /// the warning is drawn over it. It does nothing in release builds.
+  pub fn analyze(&mut self, graphics: &mut Graphics) {
+    if !cfg!(debug_assertions) {
+      return;
+    }
+    let now = unsafe { CApiState::get().csystem.getCurrentTimeMilliseconds.unwrap()() };
+
+    if let Some(transition) = self.compare_frame(graphics) {
+      // Consecutive changes in the same direction are part of the same half of a flash.
+      if self.transitions.back().map(|(_, t)| *t) != Some(transition) {
+        self.transitions.push_back((now, transition));
+      }
+    }
+    while let Some((time, _)) = self.transitions.front() {
+      if now.wrapping_sub(*time) <= WINDOW_MS {
+        break;
+      }
+      self.transitions.pop_front();
+    }
+
+    let flashes = self.transitions.len() as u32 / 2;
+    if flashes > self.max_flashes_per_second {
+      if self.last_flashing.is_none() {
+        crate::log::log(format!(
+          "FlashAnalyzer: {} flashes in the last second, which is more than the {} allowed.{}",
+          flashes,
+          self.max_flashes_per_second,
+          if System::new().is_reduce_flashing_enabled() {
+            " The reduce flashing setting is enabled."
+          } else {
+            ""
+          }
+        ));
+      }
+      self.last_flashing = Some(now);
+    } else if let Some(last) = self.last_flashing {
+      if now.wrapping_sub(last) > OVERLAY_MS {
+        self.last_flashing = None;
+        draw_warning(graphics, false);
+      }
+    }
+
+    // The warning is drawn every frame while it is shown, in case other drawing to the debug layer
+    // has erased part of it.
+    if self.last_flashing.is_some() {
+      draw_warning(graphics, true);
+    }
+  }
+
+  /// Compares the displayed frame to the previous one, returning the direction that enough of the
+  /// display changed brightness in, if any. Then saves the displayed frame for next time.
+  fn compare_frame(&mut self, graphics: &Graphics) -> Option<Transition> {
+    let rows = graphics.displayed_frame_rows();
+    let bytes = rows.as_bytes();
+    if self.previous.is_empty() {
+      self.previous.extend_from_slice(bytes);
+      return None;
+    }
+
+    let (mut brighter, mut darker) = (0, 0);
+    for y in 0..LCD_ROWS as usize {
+      let start = y * LCD_ROWBYTES as usize;
+      // The bytes past the visible columns are padding.
+      let end = start + LCD_COLUMNS as usize / 8;
+      for (now, before) in bytes[start..end].iter().zip(&self.previous[start..end]) {
+        let changed = now ^ before;
+        // A set bit is a white pixel.
+        brighter += (changed & now).count_ones();
+        darker += (changed & before).count_ones();
+      }
+    }
+    self.previous.copy_from_slice(bytes);
+
+    let threshold = ((self.area_threshold * (LCD_COLUMNS * LCD_ROWS) as f32) as u32).max(1);
+    if brighter >= threshold && brighter >= darker {
+      Some(Transition::Brighter)
+    } else if darker >= threshold {
+      Some(Transition::Darker)
+    } else {
+      None
+    }
+  }
+}
+impl Default for FlashAnalyzer {
+  fn default() -> Self {
+    FlashAnalyzer::new()
+  }
+}
+
+/// Draws the warning over the edges of the `DebugLayer`, or erases it when `shown` is false. Only
+/// the parts of the debug layer that the warning covers are erased, so other debug drawing is kept.
+fn draw_warning(graphics: &mut Graphics, shown: bool) {
+  let color = if shown {
+    DebugColor::Highlight
+  } else {
+    DebugColor::Clear
+  };
+  let mut layer = graphics.debug_layer();
+  let screen = euclid::rect(0, 0, LCD_COLUMNS as i32, LCD_ROWS as i32);
+  layer.draw_rect(screen, color);
+  layer.draw_rect(screen.inflate(-1, -1), color);
+  layer.fill_rect(euclid::rect(4, 4, 136, 20), DebugColor::Clear);
+  if shown {
+    layer.draw_text("Flashing warning", 6, 6);
+  }
+}
@@ -0,0 +1,255 @@
+use super::bitmap::Bitmap;
+use super::color::{Color, Pattern, PixelColor};
+use super::graphics::Graphics;
+use crate::ctypes::*;
+use crate::grid::BitGrid;
+
+/// A fog of war over a grid of cells, which hides the parts of a map that the player has not
+/// explored, and dims the parts they have explored but can not currently see.
+///
+/// Each cell is either hidden, which is drawn solid black; explored, which is drawn with the dim
+/// pattern so the map shows through; or visible, which is not covered at all. Cells become visible
+/// and explored when revealed with `reveal_rect()` or `reveal_circle()`, and stop being visible,
+/// while staying explored, with `clear_visible()`. This is usually done each turn or frame, before
+/// revealing the area around the player again.
+///
+/// The fog is drawn from an overlay bitmap covering the whole grid. Only the cells that changed
+/// since the overlay was last drawn are redrawn into it, with stencils built from the explored and
+/// visible cells, so a fog over a large map costs little to draw when little of it changes.
+#[derive(Debug)]
+pub struct Fog {
+  cell_size: i32,
+  explored: BitGrid,
+  visible: BitGrid,
+  dim_pattern: Pattern,
+  // The fog image, covering every cell of the grid.
+  overlay: Bitmap,
+  // Stencils the size of the overlay, which are white where the cells are hidden and dimmed
+  // respectively.
+  hidden_stencil: Bitmap,
+  dim_stencil: Bitmap,
+  // The cells which have changed since the overlay was last drawn.
+  changed: Option<euclid::default::Box2D<i32>>,
+  // The cells which have been revealed since `clear_visible()`.
+  visible_bounds: Option<euclid::default::Box2D<i32>>,
+}
+impl Fog {
+  /// Constructs a fog over a grid of `columns` by `rows` cells, where each cell is `cell_size` by
+  /// `cell_size` pixels on the screen. Every cell starts out hidden.
+  pub fn new(columns: i32, rows: i32, cell_size: i32) -> Fog {
+    assert!(columns > 0 && rows > 0 && cell_size > 0);
+    // Stencils which are smaller than the screen should have a width that is a multiple of 32
+    // pixels, so all the bitmaps are made that wide.
+    let width = (columns * cell_size + 31) / 32 * 32;
+    let height = rows * cell_size;
+
+    let mut overlay = Bitmap::new(width, height, SolidColor::kColorBlack);
+    let mask = Bitmap::new(width, height, SolidColor::kColorBlack);
+    // The mask is the same size as the bitmap, so this can not fail.
+    overlay.set_mask_bitmap(&mask).unwrap();
+
+    Fog {
+      cell_size,
+      explored: BitGrid::new(columns, rows),
+      visible: BitGrid::new(columns, rows),
+      dim_pattern: default_dim_pattern(),
+      overlay,
+      hidden_stencil: Bitmap::new(width, height, SolidColor::kColorBlack),
+      dim_stencil: Bitmap::new(width, height, SolidColor::kColorBlack),
+      changed: Some(euclid::default::Box2D::new(
+        euclid::point2(0, 0),
+        euclid::point2(columns, rows),
+      )),
+      visible_bounds: None,
+    }
+  }
+
+  /// The number of columns of cells in the fog.
+  pub fn columns(&self) -> i32 {
+    self.explored.width()
+  }
+  /// The number of rows of cells in the fog.
+  pub fn rows(&self) -> i32 {
+    self.explored.height()
+  }
+  /// The width and height of each cell, in pixels.
+  pub fn cell_size(&self) -> i32 {
+    self.cell_size
+  }
+
+  /// Sets the pattern drawn over cells which are explored but not visible. Pixels that are masked
+  /// out of the pattern let the map show through.
+  ///
+  /// The default pattern covers every other pixel in black.
+  pub fn set_dim_pattern(&mut self, pattern: Pattern) {
+    self.dim_pattern = pattern;
+    self.mark_all_changed();
+  }
+
+  /// The cells which have been explored, which includes every visible cell.
+  pub fn explored(&self) -> &BitGrid {
+    &self.explored
+  }
+  /// The cells which are currently visible.
+  pub fn visible(&self) -> &BitGrid {
+    &self.visible
+  }
+  /// Whether the cell at (`x`, `y`) has been explored.
+  pub fn is_explored(&self, x: i32, y: i32) -> bool {
+    self.explored.get(x, y)
+  }
+  /// Whether the cell at (`x`, `y`) is currently visible.
+  pub fn is_visible(&self, x: i32, y: i32) -> bool {
+    self.visible.get(x, y)
+  }
+
+  /// Makes the cells in `rect` visible and explored.
+  pub fn reveal_rect(&mut self, rect: euclid::default::Rect<i32>) {
+    if let Some(r) = self.clip(rect) {
+      self.visible.fill_rect(r, true);
+      self.explored.fill_rect(r, true);
+      self.mark_revealed(r);
+    }
+  }
+  /// Makes the cells within `radius` cells of the `center` cell visible and explored.
+  pub fn reveal_circle(&mut self, center: euclid::default::Point2D<i32>, radius: i32) {
+    if radius < 0 {
+      return;
+    }
+    for dy in -radius..=radius {
+      // The cells in this row whose centers are within the radius of the center cell.
+      let half_width = ((radius * radius - dy * dy) as u32).isqrt() as i32;
+      let row = euclid::rect(center.x - half_width, center.y + dy, half_width * 2 + 1, 1);
+      self.reveal_rect(row);
+    }
+  }
+  /// Marks the cells in `rect` as explored without making them visible, such as when the player
+  /// finds a map of the area.
+  pub fn explore_rect(&mut self, rect: euclid::default::Rect<i32>) {
+    if let Some(r) = self.clip(rect) {
+      self.explored.fill_rect(r, true);
+      self.mark_changed(r);
+    }
+  }
+  /// Makes every visible cell no longer visible. The cells remain explored.
+  pub fn clear_visible(&mut self) {
+    if let Some(bounds) = self.visible_bounds.take() {
+      self.visible.fill(false);
+      self.mark_changed(bounds.to_rect());
+    }
+  }
+  /// Hides every cell, as if nothing had been explored.
+  pub fn reset(&mut self) {
+    self.visible.fill(false);
+    self.explored.fill(false);
+    self.visible_bounds = None;
+    self.mark_all_changed();
+  }
+
+  /// Redraws the overlay bitmap for any cells that have changed since it was last drawn.
+  ///
+  /// This is done by `draw()`, but can be called separately to prepare the `overlay()` bitmap.
+  pub fn update(&mut self, graphics: &mut Graphics) {
+    let changed = match self.changed.take() {
+      Some(changed) => changed,
+      None => return,
+    };
+    self.update_stencils(changed.min.y, changed.max.y);
+
+    let pixels = changed.to_rect() * self.cell_size;
+    // This pushes and pops the overlay around its drawing, so it does not need to be tracked in the
+    // ContextStack.
+    unsafe { Graphics::fns().pushContext.unwrap()(self.overlay.cptr() as *mut _) };
+    graphics.fill_rect(pixels, Color::Solid(SolidColor::kColorClear));
+    {
+      let _stencil = graphics.set_stencil(&self.dim_stencil);
+      graphics.fill_rect(pixels, Color::Pattern(&self.dim_pattern));
+    }
+    {
+      let _stencil = graphics.set_stencil(&self.hidden_stencil);
+      graphics.fill_rect(pixels, Color::Solid(SolidColor::kColorBlack));
+    }
+    unsafe { Graphics::fns().popContext.unwrap()() };
+  }
+
+  /// Draws the fog with the top-left of its first cell at (`x`, `y`), after redrawing any cells in
+  /// the overlay that have changed.
+  pub fn draw(&mut self, graphics: &mut Graphics, x: i32, y: i32) {
+    self.update(graphics);
+    graphics.draw_bitmap(&self.overlay, x, y, BitmapFlip::kBitmapUnflipped);
+  }
+
+  /// The bitmap holding the fog's image, which is transparent where cells are visible.
+  ///
+  /// It is only up to date with the cells after calling `update()` or `draw()`.
+  pub fn overlay(&self) -> &Bitmap {
+    &self.overlay
+  }
+
+  /// Rebuilds the rows of the stencils that cover the cell rows from `first_row` up to (but not
+  /// including) `end_row`.
+  fn update_stencils(&mut self, first_row: i32, end_row: i32) {
+    let cs = self.cell_size;
+    let width = self.overlay.data().width();
+    let row_bytes = self.hidden_stencil.data().row_bytes() as usize;
+    let mut hidden = BitGrid::new(width, 1);
+    let mut dim = BitGrid::new(width, 1);
+    for cell_y in first_row..end_row {
+      hidden.fill(false);
+      dim.fill(false);
+      for cell_x in 0..self.columns() {
+        let pixels = euclid::rect(cell_x * cs, 0, cs, 1);
+        if !self.explored.get(cell_x, cell_y) {
+          hidden.fill_rect(pixels, true);
+        } else if !self.visible.get(cell_x, cell_y) {
+          dim.fill_rect(pixels, true);
+        }
+      }
+      // The BitGrid rows have the same layout as the bitmap rows, so they can be copied directly to
+      // each row of pixels in the cell.
+      let len = row_bytes.min(hidden.row_bytes() as usize);
+      for (stencil, bits) in [(&mut self.hidden_stencil, &hidden), (&mut self.dim_stencil, &dim)] {
+        let bytes = stencil.as_mut_bytes();
+        for y in cell_y * cs..(cell_y + 1) * cs {
+          let start = y as usize * row_bytes;
+          bytes[start..start + len].copy_from_slice(&bits.row(0)[..len]);
+        }
+      }
+    }
+  }
+
+  fn clip(&self, rect: euclid::default::Rect<i32>) -> Option<euclid::default::Rect<i32>> {
+    let bounds = euclid::default::Rect::from_size(euclid::size2(self.columns(), self.rows()));
+    rect.intersection(&bounds)
+  }
+  fn mark_revealed(&mut self, rect: euclid::default::Rect<i32>) {
+    let b = rect.to_box2d();
+    self.visible_bounds = Some(match self.visible_bounds {
+      Some(v) => v.union(&b),
+      None => b,
+    });
+    self.mark_changed(rect);
+  }
+  fn mark_changed(&mut self, rect: euclid::default::Rect<i32>) {
+    let b = rect.to_box2d();
+    self.changed = Some(match self.changed {
+      Some(c) => c.union(&b),
+      None => b,
+    });
+  }
+  fn mark_all_changed(&mut self) {
+    let all = euclid::rect(0, 0, self.columns(), self.rows());
+    self.mark_changed(all)
+  }
+}
+
+/// A pattern which draws black on every other pixel, in a checkerboard, and is transparent between.
+fn default_dim_pattern() -> Pattern {
+  let mut colors = [None; 8 * 8];
+  for (i, c) in colors.iter_mut().enumerate() {
+    if (i % 8 + i / 8) % 2 == 0 {
+      *c = Some(PixelColor::BLACK);
+    }
+  }
+  Pattern::new_masked(colors)
+}
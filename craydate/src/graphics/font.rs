@@ -74,13 +74,20 @@ impl Font {
   /// Each FontPage contains information for 256 characters. All chars with the same high 24 bits
   /// share a page; specifically, if `(c1 & ~0xff) == (c2 & ~0xff)`, then c1 and c2 belong to the
   /// same page. The FontPage can be used to query information about all characters in the page.
-  pub fn font_page(&self, c: char) -> FontPage {
+  ///
+  /// Returns None if the font has no page for the character, which means it has no glyphs for any
+  /// of the characters in the page.
+  pub fn font_page(&self, c: char) -> Option<FontPage> {
     // getFontPage() takes a mutable pointer but does not write to the data.
     let page_ptr = unsafe { Self::fns().getFontPage.unwrap()(self.cptr() as *mut _, c as u32) };
-    FontPage {
-      page_ptr: unsafe { NonNull::new_unchecked(page_ptr) },
+    Some(FontPage {
+      page_ptr: NonNull::new(page_ptr)?,
       page_test: c as u32 & 0xffffff00,
-    }
+    })
+  }
+  /// Returns the glyph for the character `c`, or None if the font has no glyph for it.
+  pub(crate) fn glyph(&self, c: char) -> Option<FontGlyph> {
+    self.font_page(c)?.glyph(c)
   }
 
   pub(crate) fn cptr(&self) -> *const CFont {
@@ -113,9 +120,10 @@ impl FontPage {
 
   /// Returns the glyph for the character `c`.
   ///
-  /// May return None if the character is not part of this FontPage. Each FontPage contains
-  /// information for 256 characters. All chars with the same high 24 bits share a page;
-  /// specifically, if `(c1 & ~0xff) == (c2 & ~0xff)`, then c1 and c2 belong to the same page.
+  /// Returns None if the character is not part of this FontPage, or if the font has no glyph for
+  /// the character. Each FontPage contains information for 256 characters. All chars with the same
+  /// high 24 bits share a page; specifically, if `(c1 & ~0xff) == (c2 & ~0xff)`, then c1 and c2
+  /// belong to the same page.
   pub fn glyph(&self, c: char) -> Option<FontGlyph> {
     if !self.contains(c) {
       None
@@ -133,11 +141,12 @@ impl FontPage {
           &mut advance,
         )
       };
+      // The font may not have a glyph for every character in the page.
       Some(FontGlyph {
-        glyph_ptr: NonNull::new(glyph_ptr).unwrap(),
+        glyph_ptr: NonNull::new(glyph_ptr)?,
         advance,
         glyph_char: c,
-        bitmap: UnownedBitmapRef::<'static>::from_ptr(NonNull::new(bitmap_ptr).unwrap()),
+        bitmap: UnownedBitmapRef::<'static>::from_ptr(NonNull::new(bitmap_ptr)?),
       })
     }
   }
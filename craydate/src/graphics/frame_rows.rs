@@ -0,0 +1,100 @@
+use core::marker::PhantomData;
+
+use super::graphics::Graphics;
+use crate::ctypes_enums::{LCD_ROWBYTES, LCD_ROWS};
+
+/// Direct access to the rows of bytes in the working frame buffer, which is being drawn to and will
+/// be displayed at the end of the frame.
+///
+/// Each row is `LCD_ROWBYTES` bytes long, and each byte holds 8 pixels, where a bit of 1 is a
+/// white pixel and the highest bit is the leftmost pixel.
+///
+/// Rows that are changed through `row_mut()` or `as_mut_bytes()` are marked as updated when the
+/// `WorkingFrameRows` is dropped, as with `Graphics::mark_updated_rows()`, so that the changes are
+/// sent to the display.
+///
+/// This always accesses the display's frame buffer, regardless of any bitmap pushed as the drawing
+/// target with `Graphics::push_context_bitmap()`.
+#[derive(Debug)]
+pub struct WorkingFrameRows<'a> {
+  ptr: *mut u8,
+  // The first and last rows changed, if any were.
+  updated: Option<(usize, usize)>,
+  _marker: PhantomData<&'a mut Graphics>,
+}
+impl<'a> WorkingFrameRows<'a> {
+  pub(crate) fn new(ptr: *mut u8) -> Self {
+    assert!(!ptr.is_null());
+    WorkingFrameRows {
+      ptr,
+      updated: None,
+      _marker: PhantomData,
+    }
+  }
+
+  /// Gives read access to the bytes of the row `y`.
+  pub fn row(&self, y: usize) -> &[u8] {
+    &self.as_bytes()[y * LCD_ROWBYTES as usize..(y + 1) * LCD_ROWBYTES as usize]
+  }
+  /// Gives read-write access to the bytes of the row `y`, and marks the row as updated.
+  pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+    assert!(y < LCD_ROWS as usize);
+    self.mark_updated(y, y);
+    let bytes =
+      unsafe { core::slice::from_raw_parts_mut(self.ptr, (LCD_ROWBYTES * LCD_ROWS) as usize) };
+    &mut bytes[y * LCD_ROWBYTES as usize..(y + 1) * LCD_ROWBYTES as usize]
+  }
+  /// Gives read access to the whole frame buffer as an array of bytes, one row after another.
+  pub fn as_bytes(&self) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(self.ptr, (LCD_ROWBYTES * LCD_ROWS) as usize) }
+  }
+  /// Gives read-write access to the whole frame buffer as an array of bytes, one row after another,
+  /// and marks every row as updated.
+  pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+    self.mark_updated(0, LCD_ROWS as usize - 1);
+    unsafe { core::slice::from_raw_parts_mut(self.ptr, (LCD_ROWBYTES * LCD_ROWS) as usize) }
+  }
+
+  fn mark_updated(&mut self, start: usize, end: usize) {
+    self.updated = Some(match self.updated {
+      Some((s, e)) => (s.min(start), e.max(end)),
+      None => (start, end),
+    });
+  }
+}
+impl Drop for WorkingFrameRows<'_> {
+  fn drop(&mut self) {
+    if let Some((start, end)) = self.updated {
+      unsafe { Graphics::fns().markUpdatedRows.unwrap()(start as i32, end as i32) }
+    }
+  }
+}
+
+/// Read access to the rows of bytes in the displayed frame buffer, which holds the frame that is
+/// currently shown on the display.
+///
+/// Each row is `LCD_ROWBYTES` bytes long, and each byte holds 8 pixels, where a bit of 1 is a
+/// white pixel and the highest bit is the leftmost pixel.
+#[derive(Debug)]
+pub struct DisplayedFrameRows<'a> {
+  ptr: *const u8,
+  _marker: PhantomData<&'a Graphics>,
+}
+impl<'a> DisplayedFrameRows<'a> {
+  pub(crate) fn new(ptr: *const u8) -> Self {
+    assert!(!ptr.is_null());
+    DisplayedFrameRows {
+      ptr,
+      _marker: PhantomData,
+    }
+  }
+
+  /// Gives read access to the bytes of the row `y`.
+  pub fn row(&self, y: usize) -> &[u8] {
+    &self.as_bytes()[y * LCD_ROWBYTES as usize..(y + 1) * LCD_ROWBYTES as usize]
+  }
+  /// Gives read access to the whole frame buffer as an array of bytes, one row after another.
+  pub fn as_bytes(&self) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(self.ptr, (LCD_ROWBYTES * LCD_ROWS) as usize) }
+  }
+}
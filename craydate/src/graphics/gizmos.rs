@@ -0,0 +1,242 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::graphics::Graphics;
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+use super::debug_layer::DebugColor;
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+use crate::capi_state::CApiState;
+#[cfg(all(target_arch = "arm", target_os = "none", feature = "device-gizmos"))]
+use crate::ctypes::*;
+
+/// The length, in pixels, of the lines drawn at the head of an arrow gizmo.
+const ARROW_HEAD_SIZE: f32 = 5.0;
+
+#[derive(Debug)]
+enum Gizmo {
+  Point(euclid::default::Point2D<i32>),
+  Line(euclid::default::Point2D<i32>, euclid::default::Point2D<i32>),
+  Arrow(euclid::default::Point2D<i32>, euclid::default::Point2D<i32>),
+  Rect(euclid::default::Rect<i32>),
+  Circle(euclid::default::Point2D<i32>, i32),
+  Label(euclid::default::Point2D<i32>, String),
+}
+
+/// Debug shapes, such as velocity arrows and hit boxes, which are collected while the game updates
+/// and drawn all at once after the scene is drawn.
+///
+/// Gizmos are given in world coordinates, and are moved onto the screen by the world offset set
+/// with `set_world_offset()`, which would usually follow the game's camera. This lets game logic
+/// add gizmos wherever it has the information, without needing to know about drawing or the
+/// camera.
+///
+/// In the simulator, `flush()` draws the gizmos to the `DebugLayer` so they appear in the debug
+/// color over the frame, with labels in the system's default font. On the device there is no debug
+/// layer, so gizmos are discarded unless the `device-gizmos` feature of the crate is enabled, in
+/// which case they are drawn over the frame with `SolidColor::kColorXOR`.
+#[derive(Debug, Default)]
+pub struct Gizmos {
+  gizmos: Vec<Gizmo>,
+  world_offset: euclid::default::Vector2D<i32>,
+  // The areas of the debug layer that the last flush drew to, which are erased by the next one.
+  drawn: Vec<euclid::default::Rect<i32>>,
+}
+impl Gizmos {
+  /// Constructs an empty set of gizmos with no world offset.
+  pub fn new() -> Self {
+    Gizmos::default()
+  }
+
+  /// Sets the offset that moves gizmos from world coordinates to the screen. A gizmo at `p` in the
+  /// world is drawn at `p + offset` on the screen.
+  ///
+  /// The offset used is the one set when the gizmos are flushed.
+  pub fn set_world_offset(&mut self, offset: euclid::default::Vector2D<i32>) {
+    self.world_offset = offset;
+  }
+
+  /// Adds a single pixel at `p`.
+  pub fn point(&mut self, p: euclid::default::Point2D<i32>) {
+    self.gizmos.push(Gizmo::Point(p))
+  }
+  /// Adds a line from `from` to `to`.
+  pub fn line(&mut self, from: euclid::default::Point2D<i32>, to: euclid::default::Point2D<i32>) {
+    self.gizmos.push(Gizmo::Line(from, to))
+  }
+  /// Adds an arrow from `from`, with its head at `to`.
+  pub fn arrow(&mut self, from: euclid::default::Point2D<i32>, to: euclid::default::Point2D<i32>) {
+    self.gizmos.push(Gizmo::Arrow(from, to))
+  }
+  /// Adds the outline of `rect`.
+  pub fn rect(&mut self, rect: euclid::default::Rect<i32>) {
+    self.gizmos.push(Gizmo::Rect(rect))
+  }
+  /// Adds the outline of a circle centered at `center`.
+  pub fn circle(&mut self, center: euclid::default::Point2D<i32>, radius: i32) {
+    self.gizmos.push(Gizmo::Circle(center, radius))
+  }
+  /// Adds the `text` with its upper-left corner at `anchor`.
+  pub fn label(&mut self, anchor: euclid::default::Point2D<i32>, text: &str) {
+    self.gizmos.push(Gizmo::Label(anchor, String::from(text)))
+  }
+
+  /// Removes all the gizmos without drawing them.
+  pub fn clear(&mut self) {
+    self.gizmos.clear()
+  }
+
+  /// Draws all the gizmos added since the last flush, and removes them.
+  ///
+  /// This should be called after the scene is drawn for the frame. In the simulator, the areas of
+  /// the debug layer that the gizmos drew to at the last flush are erased first, so that only this
+  /// frame's gizmos are shown. Other drawing to the debug layer is kept, unless it is in one of
+  /// those areas.
+  pub fn flush(&mut self, graphics: &mut Graphics) {
+    let offset = self.world_offset;
+    let mut canvas = GizmoCanvas::new(graphics, &mut self.drawn);
+    for gizmo in self.gizmos.drain(..) {
+      match gizmo {
+        Gizmo::Point(p) => canvas.line(p + offset, p + offset),
+        Gizmo::Line(from, to) => canvas.line(from + offset, to + offset),
+        Gizmo::Arrow(from, to) => {
+          let (from, to) = (from + offset, to + offset);
+          canvas.line(from, to);
+          let direction = (to - from).to_f32();
+          if direction.square_length() > 0.0 {
+            let back = -direction.normalize() * ARROW_HEAD_SIZE;
+            let side = euclid::vec2(-back.y, back.x) * 0.5;
+            let tip = to.to_f32();
+            canvas.line(to, (tip + back + side).round().to_i32());
+            canvas.line(to, (tip + back - side).round().to_i32());
+          }
+        }
+        Gizmo::Rect(rect) => canvas.rect(rect.translate(offset)),
+        Gizmo::Circle(center, radius) => {
+          let c = center + offset;
+          canvas.elipse(euclid::rect(
+            c.x - radius,
+            c.y - radius,
+            radius * 2,
+            radius * 2,
+          ));
+        }
+        Gizmo::Label(anchor, text) => {
+          let a = anchor + offset;
+          canvas.text(&text, a.x, a.y);
+        }
+      }
+    }
+  }
+}
+
+/// Where gizmos are drawn, which depends on the build.
+struct GizmoCanvas<'a> {
+  #[cfg_attr(
+    all(
+      target_arch = "arm",
+      target_os = "none",
+      not(feature = "device-gizmos")
+    ),
+    allow(dead_code)
+  )]
+  graphics: &'a mut Graphics,
+  // The areas of the debug layer drawn to, for the next flush to erase.
+  #[cfg_attr(all(target_arch = "arm", target_os = "none"), allow(dead_code))]
+  drawn: &'a mut Vec<euclid::default::Rect<i32>>,
+}
+impl<'a> GizmoCanvas<'a> {
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn new(graphics: &'a mut Graphics, drawn: &'a mut Vec<euclid::default::Rect<i32>>) -> Self {
+    // Erase only what the gizmos drew last time, rather than clearing the whole debug layer, which
+    // may have other things drawn to it.
+    let mut layer = graphics.debug_layer();
+    for rect in drawn.drain(..) {
+      layer.fill_rect(rect, DebugColor::Clear);
+    }
+    GizmoCanvas { graphics, drawn }
+  }
+  #[cfg(all(target_arch = "arm", target_os = "none"))]
+  fn new(graphics: &'a mut Graphics, drawn: &'a mut Vec<euclid::default::Rect<i32>>) -> Self {
+    GizmoCanvas { graphics, drawn }
+  }
+
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn line(&mut self, from: euclid::default::Point2D<i32>, to: euclid::default::Point2D<i32>) {
+    let (min, max) = (from.min(to), from.max(to));
+    self.drawn.push(euclid::rect(
+      min.x,
+      min.y,
+      max.x - min.x + 1,
+      max.y - min.y + 1,
+    ));
+    self.graphics.debug_layer().draw_line(from, to, 1, DebugColor::Highlight)
+  }
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn rect(&mut self, rect: euclid::default::Rect<i32>) {
+    self.drawn.push(rect);
+    self.graphics.debug_layer().draw_rect(rect, DebugColor::Highlight)
+  }
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn elipse(&mut self, rect: euclid::default::Rect<i32>) {
+    self.drawn.push(rect);
+    self.graphics.debug_layer().draw_elipse(rect, 1, DebugColor::Highlight)
+  }
+  #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+  fn text(&mut self, text: &str, x: i32, y: i32) {
+    // Labels are drawn in a known font, rather than whatever font the game has set, so that the
+    // area they cover can be measured to erase it later. The default font is loaded at startup, and
+    // if that failed the label is not drawn.
+    if let Some(font) = CApiState::get().default_font {
+      let width = font.measure_text_width(text, 0);
+      self.drawn.push(euclid::rect(x, y, width, font.font_height() as i32));
+      self.graphics.debug_layer().draw_text_in_font(text, font, x, y)
+    }
+  }
+
+  #[cfg(all(target_arch = "arm", target_os = "none", feature = "device-gizmos"))]
+  fn line(&mut self, from: euclid::default::Point2D<i32>, to: euclid::default::Point2D<i32>) {
+    self.graphics.draw_line(from, to, 1, SolidColor::kColorXOR.into())
+  }
+  #[cfg(all(target_arch = "arm", target_os = "none", feature = "device-gizmos"))]
+  fn rect(&mut self, rect: euclid::default::Rect<i32>) {
+    self.graphics.draw_rect(rect, SolidColor::kColorXOR.into())
+  }
+  #[cfg(all(target_arch = "arm", target_os = "none", feature = "device-gizmos"))]
+  fn elipse(&mut self, rect: euclid::default::Rect<i32>) {
+    self.graphics.draw_elipse(rect, 1, 0.0, 0.0, SolidColor::kColorXOR.into())
+  }
+  #[cfg(all(target_arch = "arm", target_os = "none", feature = "device-gizmos"))]
+  fn text(&mut self, text: &str, x: i32, y: i32) {
+    // The draw mode can not be read back to restore it, so the text is drawn in a pushed context,
+    // which is popped to put back the game's draw mode. A null target is the display.
+    unsafe { Graphics::fns().pushContext.unwrap()(core::ptr::null_mut()) };
+    self.graphics.set_draw_mode(BitmapDrawMode::kDrawModeXOR);
+    self.graphics.draw_text(text, x, y);
+    unsafe { Graphics::fns().popContext.unwrap()() };
+  }
+
+  #[cfg(all(
+    target_arch = "arm",
+    target_os = "none",
+    not(feature = "device-gizmos")
+  ))]
+  fn line(&mut self, _: euclid::default::Point2D<i32>, _: euclid::default::Point2D<i32>) {}
+  #[cfg(all(
+    target_arch = "arm",
+    target_os = "none",
+    not(feature = "device-gizmos")
+  ))]
+  fn rect(&mut self, _: euclid::default::Rect<i32>) {}
+  #[cfg(all(
+    target_arch = "arm",
+    target_os = "none",
+    not(feature = "device-gizmos")
+  ))]
+  fn elipse(&mut self, _: euclid::default::Rect<i32>) {}
+  #[cfg(all(
+    target_arch = "arm",
+    target_os = "none",
+    not(feature = "device-gizmos")
+  ))]
+  fn text(&mut self, _: &str, _: i32, _: i32) {}
+}
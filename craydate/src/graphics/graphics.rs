@@ -6,7 +6,9 @@ use super::bitmap::{Bitmap, BitmapRef};
 use super::bitmap_collider::BitmapCollider;
 use super::color::Color;
 use super::context_stack::ContextStackId;
+use super::debug_layer::DebugLayer;
 use super::font::Font;
+use super::frame_rows::{DisplayedFrameRows, WorkingFrameRows};
 use super::framebuffer_stencil_bitmap::FramebufferStencilBitmap;
 #[cfg(not(all(target_arch = "arm", target_os = "none")))]
 use super::unowned_bitmap::UnownedBitmapMut;
@@ -84,6 +86,13 @@ impl Graphics {
     UnownedBitmapMut::from_ptr(NonNull::new(bitmap_ptr).unwrap())
   }
 
+  /// Returns the simulator's debug layer, for drawing over the frame in the simulator's debug color.
+  ///
+  /// The layer can be drawn to in every build, but drawing to it does nothing on the device.
+  pub fn debug_layer(&mut self) -> DebugLayer<'_> {
+    DebugLayer::new(self)
+  }
+
   /// Returns a copy of the contents of the display front buffer.
   ///
   /// The Playdate device is double-buffered, and this returns the currently displayed frame.
@@ -102,7 +111,24 @@ impl Graphics {
     Bitmap::from_owned_ptr(NonNull::new(bitmap_ptr).unwrap())
   }
 
-  /// After updating pixels in the buffer returned by `get_frame()`, you must tell the graphics
+  /// Gives direct access to the rows of the working frame buffer, which is being drawn to and will
+  /// be displayed at the end of the frame.
+  ///
+  /// Changes made through the returned `WorkingFrameRows` are marked as updated when it is dropped,
+  /// so there's no need to call `mark_updated_rows()` for them.
+  pub fn working_frame_rows(&mut self) -> WorkingFrameRows<'_> {
+    WorkingFrameRows::new(unsafe { Self::fns().getFrame.unwrap()() })
+  }
+  /// Gives direct read access to the rows of the displayed frame buffer, which holds the frame that
+  /// is currently shown on the display.
+  ///
+  /// The Playdate device is double-buffered, so this is the previous frame, not the one being drawn
+  /// to.
+  pub fn displayed_frame_rows(&self) -> DisplayedFrameRows<'_> {
+    DisplayedFrameRows::new(unsafe { Self::fns().getDisplayFrame.unwrap()() })
+  }
+
+  /// After updating pixels in the working frame buffer directly, you must tell the graphics
   /// system which rows were updated. This function marks a contiguous range of rows as updated
   /// (e.g., `mark_updated_rows(0, LCD_ROWS - 1)` tells the system to update the entire display).
   /// Both "start" and "end" are included in the range.
@@ -205,6 +231,11 @@ impl Graphics {
     }
   }
 
+  /// Clears the current clip rect, so that drawing is not clipped.
+  pub fn clear_clip_rect(&mut self) {
+    unsafe { Self::fns().clearClipRect.unwrap()() }
+  }
+
   /// Sets the mode used for drawing bitmaps. Note that text drawing uses bitmaps, so this
   /// affects how fonts are displayed as well.
   pub fn set_draw_mode(&mut self, mode: BitmapDrawMode) {
@@ -292,7 +323,7 @@ impl Graphics {
   /// Draw a text string on the screen at the given (`x`, `y`) coordinates.
   ///
   /// If no font has been set with `Graphics::set_font()`, the default system font "Asheville Sans
-  /// 14 Light" is used.
+  /// 14 Light" is used. Other fonts built into the system can be set with `Font::system()`.
   pub fn draw_text(&mut self, text: &str, x: i32, y: i32) {
     let null_term = text.to_null_terminated_utf8();
     let ptr = null_term.as_ptr() as *const c_void;
@@ -0,0 +1,87 @@
+use super::font::Font;
+use super::graphics::Graphics;
+
+/// Draws text from a `Font` at a whole-number multiple of its size, for headlines and titles.
+///
+/// Each glyph's bitmap is drawn scaled up by `scale`, so a single small font can be used for large
+/// text without shipping the font again at each size in the game's pdx. As the scale is a whole
+/// number, each pixel of the font becomes an exact square of pixels, which keeps the text crisp.
+///
+/// The glyphs are positioned with the font's advance and kerning, plus the `tracking` between each
+/// character, all multiplied by the `scale`. A `'\n'` in the text begins a new line.
+#[derive(Debug)]
+pub struct LargeText<'a> {
+  font: &'a Font,
+  scale: i32,
+  tracking: i32,
+}
+impl<'a> LargeText<'a> {
+  /// Constructs a `LargeText` to draw with `font`, where each pixel of the font is drawn as `scale`
+  /// by `scale` pixels.
+  pub fn new(font: &'a Font, scale: i32) -> Self {
+    assert!(scale > 0);
+    LargeText {
+      font,
+      scale,
+      tracking: 0,
+    }
+  }
+
+  /// Sets the number of pixels of the unscaled font to add between each character.
+  pub fn set_tracking(&mut self, tracking: i32) {
+    self.tracking = tracking;
+  }
+
+  /// The height of a line of text, in screen pixels.
+  pub fn line_height(&self) -> i32 {
+    self.font.font_height() as i32 * self.scale
+  }
+
+  /// Measures the width, in screen pixels, of the widest line in `text`.
+  pub fn measure_text_width(&self, text: &str) -> i32 {
+    text.split('\n').map(|line| self.line_width(line)).max().unwrap_or(0)
+  }
+
+  fn line_width(&self, line: &str) -> i32 {
+    let mut width = 0;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+      if let Some(glyph) = self.font.glyph(c) {
+        width += glyph.advance() + self.tracking;
+        if let Some(next) = chars.peek() {
+          width += glyph.kerning(*next);
+        }
+      }
+    }
+    // Tracking goes between characters, not after the last one.
+    if width > 0 {
+      width -= self.tracking;
+    }
+    width * self.scale
+  }
+
+  /// Draws the `text` with its upper-left corner at (`x`, `y`).
+  ///
+  /// Characters which the font has no glyph for are skipped.
+  pub fn draw(&self, graphics: &mut Graphics, text: &str, x: i32, y: i32) {
+    let scale = self.scale as f32;
+    for (line_index, line) in text.split('\n').enumerate() {
+      let line_y = y + line_index as i32 * self.line_height();
+      let mut glyph_x = x;
+      let mut chars = line.chars().peekable();
+      while let Some(c) = chars.next() {
+        let glyph = match self.font.glyph(c) {
+          Some(glyph) => glyph,
+          None => continue,
+        };
+        graphics.draw_scaled_bitmap(&glyph.bitmap(), glyph_x, line_y, scale, scale);
+
+        let mut advance = glyph.advance() + self.tracking;
+        if let Some(next) = chars.peek() {
+          advance += glyph.kerning(*next);
+        }
+        glyph_x += advance * self.scale;
+      }
+    }
+  }
+}
@@ -0,0 +1,179 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::bitmap::Bitmap;
+use super::color::{Color, PixelColor};
+use super::graphics::Graphics;
+use crate::ctypes::*;
+
+/// The width and height, in pixels, of the square drawn for each marker on a `Minimap`.
+const MARKER_SIZE: i32 = 3;
+
+/// An identifier for a marker that was added to a `Minimap` with `Minimap::add_marker()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinimapMarkerId(usize);
+
+/// A small overview image of a grid-based world, such as a tilemap or a solidity grid, which is
+/// drawn with a frame around it.
+///
+/// The world is made of `world_width` by `world_height` cells, and each pixel of the minimap covers
+/// a square block of `cells_per_pixel` by `cells_per_pixel` cells. A minimap pixel is drawn black if
+/// any cell in its block is solid, and white otherwise.
+///
+/// The minimap is kept in a `Bitmap` which is only recomputed where cells are marked as changed
+/// with `mark_cell_changed()`, so that keeping it up to date does not require walking the whole
+/// world each frame. Markers, such as the player or points of interest, are not part of the
+/// `Bitmap` and are drawn on top of it each time the minimap is drawn.
+#[derive(Debug)]
+pub struct Minimap {
+  bitmap: Bitmap,
+  world_width: i32,
+  world_height: i32,
+  cells_per_pixel: i32,
+  // Indices of minimap pixels that need to be recomputed in the next `update()`.
+  dirty: Vec<usize>,
+  // Whether each minimap pixel is already present in `dirty`.
+  dirty_flags: Vec<bool>,
+  markers: BTreeMap<usize, euclid::default::Point2D<i32>>,
+  next_marker_id: usize,
+}
+impl Minimap {
+  /// Constructs a minimap for a world of `world_width` by `world_height` cells, where each pixel of
+  /// the minimap covers `cells_per_pixel` by `cells_per_pixel` cells.
+  ///
+  /// The minimap starts out entirely dirty, so the first call to `update()` will compute every
+  /// pixel.
+  pub fn new(world_width: i32, world_height: i32, cells_per_pixel: i32) -> Minimap {
+    assert!(world_width > 0 && world_height > 0);
+    assert!(cells_per_pixel > 0);
+    let width = (world_width + cells_per_pixel - 1) / cells_per_pixel;
+    let height = (world_height + cells_per_pixel - 1) / cells_per_pixel;
+    let pixel_count = (width * height) as usize;
+    Minimap {
+      bitmap: Bitmap::new(width, height, SolidColor::kColorWhite),
+      world_width,
+      world_height,
+      cells_per_pixel,
+      dirty: (0..pixel_count).collect(),
+      dirty_flags: alloc::vec![true; pixel_count],
+      markers: BTreeMap::new(),
+      next_marker_id: 1,
+    }
+  }
+
+  /// The width of the minimap in pixels, not including its frame.
+  pub fn width(&self) -> i32 {
+    (self.world_width + self.cells_per_pixel - 1) / self.cells_per_pixel
+  }
+  /// The height of the minimap in pixels, not including its frame.
+  pub fn height(&self) -> i32 {
+    (self.world_height + self.cells_per_pixel - 1) / self.cells_per_pixel
+  }
+
+  /// Marks the world cell at (`x`, `y`) as changed, so that its minimap pixel will be recomputed in
+  /// the next call to `update()`.
+  ///
+  /// Cells outside of the world are ignored.
+  pub fn mark_cell_changed(&mut self, x: i32, y: i32) {
+    if x < 0 || y < 0 || x >= self.world_width || y >= self.world_height {
+      return;
+    }
+    let index = ((y / self.cells_per_pixel) * self.width() + x / self.cells_per_pixel) as usize;
+    if !self.dirty_flags[index] {
+      self.dirty_flags[index] = true;
+      self.dirty.push(index);
+    }
+  }
+  /// Marks every world cell as changed, so the whole minimap will be recomputed in the next call to
+  /// `update()`.
+  pub fn mark_all_changed(&mut self) {
+    self.dirty.clear();
+    self.dirty.extend(0..self.dirty_flags.len());
+    for f in &mut self.dirty_flags {
+      *f = true;
+    }
+  }
+
+  /// Recomputes the minimap pixels for any cells marked as changed since the last update.
+  ///
+  /// The `is_solid` function is called with the (`x`, `y`) position of world cells, and should
+  /// return whether the cell is solid. It is only called for cells in the blocks of changed pixels.
+  pub fn update<F: Fn(i32, i32) -> bool>(&mut self, is_solid: F) {
+    if self.dirty.is_empty() {
+      return;
+    }
+    let width = self.width();
+    let cpp = self.cells_per_pixel;
+    let (world_width, world_height) = (self.world_width, self.world_height);
+
+    let mut pixels = self.bitmap.as_pixels_mut();
+    for index in self.dirty.drain(..) {
+      self.dirty_flags[index] = false;
+      let px = index as i32 % width;
+      let py = index as i32 / width;
+
+      let x_cells = px * cpp..((px + 1) * cpp).min(world_width);
+      let mut y_cells = py * cpp..((py + 1) * cpp).min(world_height);
+      let solid = y_cells.any(|y| x_cells.clone().any(|x| is_solid(x, y)));
+      let color = if solid { PixelColor::BLACK } else { PixelColor::WHITE };
+      pixels.set(px as usize, py as usize, color);
+    }
+  }
+
+  /// Adds a marker at the world cell `position`, which will be drawn on top of the minimap.
+  ///
+  /// Returns an id which can be used to move or remove the marker.
+  pub fn add_marker(&mut self, position: euclid::default::Point2D<i32>) -> MinimapMarkerId {
+    let id = self.next_marker_id;
+    self.next_marker_id += 1;
+    self.markers.insert(id, position);
+    MinimapMarkerId(id)
+  }
+  /// Moves the marker with the given `id` to the world cell `position`.
+  ///
+  /// Does nothing if the marker was removed.
+  pub fn move_marker(&mut self, id: MinimapMarkerId, position: euclid::default::Point2D<i32>) {
+    if let Some(p) = self.markers.get_mut(&id.0) {
+      *p = position;
+    }
+  }
+  /// Removes the marker with the given `id` from the minimap.
+  pub fn remove_marker(&mut self, id: MinimapMarkerId) {
+    self.markers.remove(&id.0);
+  }
+
+  /// Draws the minimap with its upper-left corner at `position`, along with a frame around it and
+  /// any markers on top of it.
+  ///
+  /// The frame is drawn outside of the minimap, as a 1 pixel white border surrounded by a 1 pixel
+  /// black border. Markers are drawn with `SolidColor::kColorXOR` so they are visible over both
+  /// solid and empty areas.
+  pub fn draw(&self, graphics: &mut Graphics, position: euclid::default::Point2D<i32>) {
+    let (width, height) = (self.width(), self.height());
+    graphics.draw_rect(
+      euclid::rect(position.x - 2, position.y - 2, width + 4, height + 4),
+      Color::Solid(SolidColor::kColorBlack),
+    );
+    graphics.draw_rect(
+      euclid::rect(position.x - 1, position.y - 1, width + 2, height + 2),
+      Color::Solid(SolidColor::kColorWhite),
+    );
+    graphics.draw_bitmap(&self.bitmap, position.x, position.y, BitmapFlip::kBitmapUnflipped);
+
+    for marker in self.markers.values() {
+      let x = position.x + marker.x / self.cells_per_pixel - MARKER_SIZE / 2;
+      let y = position.y + marker.y / self.cells_per_pixel - MARKER_SIZE / 2;
+      graphics.fill_rect(
+        euclid::rect(x, y, MARKER_SIZE, MARKER_SIZE),
+        Color::Solid(SolidColor::kColorXOR),
+      );
+    }
+  }
+
+  /// The bitmap holding the minimap's image, without its frame or markers.
+  ///
+  /// It is only up to date with the world after calling `update()`.
+  pub fn bitmap(&self) -> &Bitmap {
+    &self.bitmap
+  }
+}
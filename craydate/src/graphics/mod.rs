@@ -3,10 +3,22 @@ mod bitmap;
 mod bitmap_collider;
 mod bitmap_data;
 mod color;
+mod contour;
+mod credits;
 mod context_stack;
+mod debug_layer;
+mod flash_analyzer;
+mod fog;
 mod font;
+mod frame_rows;
 mod framebuffer_stencil_bitmap;
+mod gizmos;
 mod graphics;
+mod large_text;
+mod minimap;
+mod qr_code;
+mod system_font;
+mod terrain;
 mod unowned_bitmap;
 mod video;
 
@@ -17,10 +29,22 @@ pub use bitmap::*;
 pub use bitmap_collider::BitmapCollider;
 pub use bitmap_data::BitmapData;
 pub use color::{Color, Pattern, PixelColor};
+pub use contour::Contour;
 pub use context_stack::ContextStackId;
+pub use credits::Credits;
+pub use debug_layer::{DebugColor, DebugLayer};
+pub use flash_analyzer::FlashAnalyzer;
+pub use fog::Fog;
 pub use font::{Font, FontGlyph, FontPage};
+pub use frame_rows::{DisplayedFrameRows, WorkingFrameRows};
 pub use framebuffer_stencil_bitmap::FramebufferStencilBitmap;
+pub use gizmos::Gizmos;
 pub use graphics::Graphics;
+pub use large_text::LargeText;
+pub use minimap::{Minimap, MinimapMarkerId};
+pub use qr_code::{QrCode, QrErrorCorrection};
+pub use system_font::SystemFont;
+pub use terrain::Terrain;
 pub use unowned_bitmap::{UnownedBitmapMut, UnownedBitmapRef};
 pub use video::Video;
 
@@ -0,0 +1,648 @@
+// The QR code encoder in this file is adapted from the QR Code generator library (Rust), which is
+// under the following license:
+//
+// Copyright (c) Project Nayuki. (MIT License)
+// https://www.nayuki.io/page/qr-code-generator-library
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy of
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+// - The above copyright notice and this permission notice shall be included in
+//   all copies or substantial portions of the Software.
+// - The Software is provided "as is", without warranty of any kind, express or
+//   implied, including but not limited to the warranties of merchantability,
+//   fitness for a particular purpose and noninfringement. In no event shall the
+//   authors or copyright holders be liable for any claim, damages or other
+//   liability, whether in an action of contract, tort or otherwise, arising from,
+//   out of or in connection with the Software or the use or other dealings in the
+//   Software.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::color::Color;
+use super::graphics::Graphics;
+use crate::ctypes::*;
+use crate::error::Error;
+use crate::grid::BitGrid;
+
+/// The number of light modules that must surround a QR code for it to be read.
+const QUIET_ZONE: i32 = 4;
+
+// The number of error correction codewords in each block, indexed by error correction level and
+// version. Version 0 does not exist.
+#[rustfmt::skip]
+const ECC_CODEWORDS_PER_BLOCK: [[i8; 41]; 4] = [
+  [-1, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+  [-1, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+  [-1, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+  [-1, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+// The number of error correction blocks, indexed by error correction level and version.
+#[rustfmt::skip]
+const NUM_ERROR_CORRECTION_BLOCKS: [[i8; 41]; 4] = [
+  [-1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+  [-1, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+  [-1, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+  [-1, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// How much of a QR code can be damaged or hidden while still being readable.
+///
+/// A higher level makes the code larger for the same data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QrErrorCorrection {
+  /// About 7% of the code can be lost.
+  Low,
+  /// About 15% of the code can be lost.
+  Medium,
+  /// About 25% of the code can be lost.
+  Quartile,
+  /// About 30% of the code can be lost.
+  High,
+}
+impl QrErrorCorrection {
+  fn index(self) -> usize {
+    match self {
+      QrErrorCorrection::Low => 0,
+      QrErrorCorrection::Medium => 1,
+      QrErrorCorrection::Quartile => 2,
+      QrErrorCorrection::High => 3,
+    }
+  }
+  /// The 2 bits that represent the level in the code's format information.
+  fn format_bits(self) -> u32 {
+    match self {
+      QrErrorCorrection::Low => 1,
+      QrErrorCorrection::Medium => 0,
+      QrErrorCorrection::Quartile => 3,
+      QrErrorCorrection::High => 2,
+    }
+  }
+}
+
+/// A QR code, which can be drawn to the screen to be read by a phone's camera.
+///
+/// The data is encoded as bytes, which phones show as text when it is valid UTF-8. The smallest QR
+/// code version that can hold the data at the requested error correction level is used, and the
+/// mask pattern is chosen to make the code easiest to read, as the QR code standard describes.
+///
+/// This is useful for sharing things that are tedious to type, such as a link to the game's website,
+/// a level code, or the details of an error for a bug report.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QrCode {
+  version: i32,
+  error_correction: QrErrorCorrection,
+  // Dark modules are set.
+  modules: BitGrid,
+}
+impl QrCode {
+  /// The largest number of bytes that a QR code can hold, at the lowest error correction level.
+  pub const MAX_BYTES: usize = 2953;
+
+  /// Encodes `data` into a QR code with the given error correction level.
+  ///
+  /// Returns `Error::QrCodeTooLong` if the data does not fit in the largest QR code at that level.
+  pub fn encode(data: &[u8], error_correction: QrErrorCorrection) -> Result<QrCode, Error> {
+    let ecl = error_correction;
+    let version = (1..=40)
+      .find(|v| data_bits_needed(*v, data.len()) <= num_data_codewords(*v, ecl) * 8)
+      .ok_or(Error::QrCodeTooLong)?;
+
+    let capacity_bits = num_data_codewords(version, ecl) * 8;
+    let mut bits = BitBuffer(Vec::with_capacity(capacity_bits));
+    // Byte mode, with the length of the data.
+    bits.append(0b0100, 4);
+    bits.append(data.len() as u32, if version <= 9 { 8 } else { 16 });
+    for b in data {
+      bits.append(*b as u32, 8);
+    }
+    // A terminator of up to 4 zero bits, then padding to a whole byte.
+    let terminator = (capacity_bits - bits.0.len()).min(4);
+    bits.append(0, terminator);
+    let to_byte = bits.0.len().wrapping_neg() & 7;
+    bits.append(0, to_byte);
+    // Alternating pad bytes fill the rest of the capacity.
+    for pad in [0xEC, 0x11].iter().cycle() {
+      if bits.0.len() >= capacity_bits {
+        break;
+      }
+      bits.append(*pad, 8);
+    }
+
+    let mut codewords = vec![0u8; bits.0.len() / 8];
+    for (i, bit) in bits.0.iter().enumerate() {
+      codewords[i >> 3] |= (*bit as u8) << (7 - (i & 7));
+    }
+    let all_codewords = add_ecc_and_interleave(&codewords, version, ecl);
+
+    let mut builder = Builder::new(version);
+    builder.draw_function_patterns(ecl);
+    builder.draw_codewords(&all_codewords);
+
+    // Try each mask, keeping the one with the lowest penalty. Applying a mask a second time undoes
+    // it.
+    let mut best = (0, i32::MAX);
+    for mask in 0..8 {
+      builder.apply_mask(mask);
+      builder.draw_format_bits(ecl, mask);
+      let penalty = builder.penalty_score();
+      if penalty < best.1 {
+        best = (mask, penalty);
+      }
+      builder.apply_mask(mask);
+    }
+    builder.apply_mask(best.0);
+    builder.draw_format_bits(ecl, best.0);
+
+    Ok(QrCode {
+      version,
+      error_correction: ecl,
+      modules: builder.modules,
+    })
+  }
+  /// Encodes the text `s` into a QR code with the given error correction level.
+  ///
+  /// Returns `Error::QrCodeTooLong` if the text does not fit in the largest QR code at that level.
+  pub fn encode_text(s: &str, error_correction: QrErrorCorrection) -> Result<QrCode, Error> {
+    QrCode::encode(s.as_bytes(), error_correction)
+  }
+
+  /// The QR code version, from 1 to 40, which determines its size.
+  pub fn version(&self) -> i32 {
+    self.version
+  }
+  /// The error correction level the code was encoded with.
+  pub fn error_correction(&self) -> QrErrorCorrection {
+    self.error_correction
+  }
+  /// The width and height of the code in modules, not including the quiet zone around it.
+  pub fn size(&self) -> i32 {
+    self.modules.width()
+  }
+  /// Whether the module at (`x`, `y`) is dark. Modules outside the code are light.
+  pub fn get(&self, x: i32, y: i32) -> bool {
+    self.modules.get(x, y)
+  }
+  /// The modules of the code, where a dark module is set.
+  pub fn modules(&self) -> &BitGrid {
+    &self.modules
+  }
+
+  /// The width and height in pixels that `draw()` covers when each module is `scale` pixels wide.
+  ///
+  /// This includes the quiet zone of light modules that is needed around the code.
+  pub fn drawn_size(&self, scale: i32) -> i32 {
+    (self.size() + QUIET_ZONE * 2) * scale
+  }
+
+  /// Draws the code with its top-left at (`x`, `y`), where each module is `scale` by `scale`
+  /// pixels.
+  ///
+  /// The code is drawn in black on white, including the quiet zone around it, so that it can be
+  /// read when drawn over anything else. The area covered is given by `drawn_size()`.
+  pub fn draw(&self, graphics: &mut Graphics, x: i32, y: i32, scale: i32) {
+    let total = self.drawn_size(scale);
+    graphics.fill_rect(
+      euclid::rect(x, y, total, total),
+      Color::Solid(SolidColor::kColorWhite),
+    );
+    let left = x + QUIET_ZONE * scale;
+    let top = y + QUIET_ZONE * scale;
+    // Each run of dark modules in a row is drawn as a single rect.
+    for my in 0..self.size() {
+      let mut mx = 0;
+      while mx < self.size() {
+        if !self.get(mx, my) {
+          mx += 1;
+          continue;
+        }
+        let start = mx;
+        while mx < self.size() && self.get(mx, my) {
+          mx += 1;
+        }
+        graphics.fill_rect(
+          euclid::rect(
+            left + start * scale,
+            top + my * scale,
+            (mx - start) * scale,
+            scale,
+          ),
+          Color::Solid(SolidColor::kColorBlack),
+        );
+      }
+    }
+  }
+}
+
+struct BitBuffer(Vec<bool>);
+impl BitBuffer {
+  /// Appends the low `len` bits of `value`, from the highest bit to the lowest.
+  fn append(&mut self, value: u32, len: usize) {
+    self.0.extend((0..len).rev().map(|i| (value >> i) & 1 != 0));
+  }
+}
+
+/// The number of bits needed to encode `len` bytes in byte mode for the `version`.
+fn data_bits_needed(version: i32, len: usize) -> usize {
+  let count_bits = if version <= 9 { 8 } else { 16 };
+  if len >= 1 << count_bits {
+    usize::MAX
+  } else {
+    4 + count_bits + len * 8
+  }
+}
+
+/// The number of modules that hold data and error correction codewords in the `version`, which is
+/// every module that is not part of a function pattern or format information.
+fn num_raw_data_modules(version: i32) -> usize {
+  let v = version;
+  let mut result = (16 * v + 128) * v + 64;
+  if v >= 2 {
+    let num_align = v / 7 + 2;
+    result -= (25 * num_align - 10) * num_align - 55;
+    if v >= 7 {
+      result -= 36;
+    }
+  }
+  result as usize
+}
+
+/// The number of data codewords, without error correction, in the `version` at the level `ecl`.
+fn num_data_codewords(version: i32, ecl: QrErrorCorrection) -> usize {
+  let ecc_len = ECC_CODEWORDS_PER_BLOCK[ecl.index()][version as usize] as usize;
+  let blocks = NUM_ERROR_CORRECTION_BLOCKS[ecl.index()][version as usize] as usize;
+  num_raw_data_modules(version) / 8 - ecc_len * blocks
+}
+
+/// The positions of the centers of the alignment patterns along each axis, in ascending order.
+fn alignment_pattern_positions(version: i32) -> Vec<i32> {
+  if version == 1 {
+    return Vec::new();
+  }
+  let size = version * 4 + 17;
+  let num_align = version / 7 + 2;
+  let step = (version * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+  let mut result: Vec<i32> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+  result.push(6);
+  result.reverse();
+  result
+}
+
+/// Splits the data codewords into blocks, adds error correction codewords to each block, and
+/// interleaves the blocks into the order they are drawn in.
+fn add_ecc_and_interleave(data: &[u8], version: i32, ecl: QrErrorCorrection) -> Vec<u8> {
+  let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[ecl.index()][version as usize] as usize;
+  let block_ecc_len = ECC_CODEWORDS_PER_BLOCK[ecl.index()][version as usize] as usize;
+  let raw_codewords = num_raw_data_modules(version) / 8;
+  // Some blocks may hold one more data codeword than others. The short blocks come first.
+  let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+  let short_block_len = raw_codewords / num_blocks;
+
+  let divisor = reed_solomon_divisor(block_ecc_len);
+  let mut blocks = Vec::with_capacity(num_blocks);
+  let mut k = 0;
+  for i in 0..num_blocks {
+    let data_len = short_block_len - block_ecc_len + if i < num_short_blocks { 0 } else { 1 };
+    let mut block = data[k..k + data_len].to_vec();
+    k += data_len;
+    let ecc = reed_solomon_remainder(&block, &divisor);
+    // A placeholder lines up the error correction codewords of short and long blocks.
+    if i < num_short_blocks {
+      block.push(0);
+    }
+    block.extend_from_slice(&ecc);
+    blocks.push(block);
+  }
+
+  let mut result = Vec::with_capacity(raw_codewords);
+  for i in 0..=short_block_len {
+    for (j, block) in blocks.iter().enumerate() {
+      // Skip the placeholders in the short blocks.
+      if i != short_block_len - block_ecc_len || j >= num_short_blocks {
+        result.push(block[i]);
+      }
+    }
+  }
+  result
+}
+
+/// The coefficients of the Reed-Solomon generator polynomial of the given `degree`, from the
+/// highest power to the lowest, without the leading 1.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+  let mut result = vec![0u8; degree];
+  result[degree - 1] = 1;
+  // Multiply together the terms (x - r^i) where r = 0x02 is a generator of GF(2^8).
+  let mut root = 1u8;
+  for _ in 0..degree {
+    for j in 0..degree {
+      result[j] = gf_multiply(result[j], root);
+      if j + 1 < degree {
+        result[j] ^= result[j + 1];
+      }
+    }
+    root = gf_multiply(root, 0x02);
+  }
+  result
+}
+
+/// The remainder of dividing `data` by the generator polynomial `divisor`, which are the error
+/// correction codewords for the data.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+  let mut result = vec![0u8; divisor.len()];
+  for b in data {
+    let factor = b ^ result[0];
+    result.rotate_left(1);
+    *result.last_mut().unwrap() = 0;
+    for (r, d) in result.iter_mut().zip(divisor) {
+      *r ^= gf_multiply(*d, factor);
+    }
+  }
+  result
+}
+
+/// Multiplies two elements of GF(2^8), modulo the polynomial 0x11D.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+  let mut z: u32 = 0;
+  for i in (0..8).rev() {
+    z = (z << 1) ^ ((z >> 7) * 0x11D);
+    z ^= ((y as u32 >> i) & 1) * x as u32;
+  }
+  z as u8
+}
+
+/// The modules of a QR code being built, along with which of them are part of function patterns
+/// and must not be masked or hold data.
+struct Builder {
+  version: i32,
+  size: i32,
+  modules: BitGrid,
+  function: BitGrid,
+}
+impl Builder {
+  fn new(version: i32) -> Self {
+    let size = version * 4 + 17;
+    Builder {
+      version,
+      size,
+      modules: BitGrid::new(size, size),
+      function: BitGrid::new(size, size),
+    }
+  }
+
+  fn set_function(&mut self, x: i32, y: i32, dark: bool) {
+    self.modules.set(x, y, dark);
+    self.function.set(x, y, true);
+  }
+
+  fn draw_function_patterns(&mut self, ecl: QrErrorCorrection) {
+    // Timing patterns.
+    for i in 0..self.size {
+      self.set_function(6, i, i % 2 == 0);
+      self.set_function(i, 6, i % 2 == 0);
+    }
+    // Finder patterns in three corners, with their separators. The ones drawn after the timing
+    // patterns overwrite them where they cross.
+    let far = self.size - 4;
+    for (cx, cy) in [(3, 3), (far, 3), (3, far)] {
+      for dy in -4i32..=4 {
+        for dx in -4i32..=4 {
+          let dist = i32::max(dx.abs(), dy.abs());
+          let (x, y) = (cx + dx, cy + dy);
+          if self.modules.contains(x, y) {
+            self.set_function(x, y, dist != 2 && dist != 4);
+          }
+        }
+      }
+    }
+    // Alignment patterns, except where they would overlap the finder patterns.
+    let positions = alignment_pattern_positions(self.version);
+    let last = positions.len().wrapping_sub(1);
+    for (i, cx) in positions.iter().enumerate() {
+      for (j, cy) in positions.iter().enumerate() {
+        let finder_corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+        if finder_corner {
+          continue;
+        }
+        for dy in -2i32..=2 {
+          for dx in -2i32..=2 {
+            let dist = i32::max(dx.abs(), dy.abs());
+            self.set_function(cx + dx, cy + dy, dist != 1);
+          }
+        }
+      }
+    }
+    // The format bits are drawn for real once a mask is chosen, but they must be marked as function
+    // modules before the codewords are drawn.
+    self.draw_format_bits(ecl, 0);
+    self.draw_version_bits();
+  }
+
+  /// Draws both copies of the format information, which holds the error correction level and the
+  /// mask.
+  fn draw_format_bits(&mut self, ecl: QrErrorCorrection, mask: u32) {
+    let data = ecl.format_bits() << 3 | mask;
+    let mut rem = data;
+    for _ in 0..10 {
+      rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    let bits = (data << 10 | rem) ^ 0x5412;
+    let bit = |i: i32| (bits >> i) & 1 != 0;
+
+    // The copy around the top-left finder pattern.
+    for i in 0..=5 {
+      self.set_function(8, i, bit(i));
+    }
+    self.set_function(8, 7, bit(6));
+    self.set_function(8, 8, bit(7));
+    self.set_function(7, 8, bit(8));
+    for i in 9..15 {
+      self.set_function(14 - i, 8, bit(i));
+    }
+    // The copy split between the other two finder patterns.
+    let size = self.size;
+    for i in 0..8 {
+      self.set_function(size - 1 - i, 8, bit(i));
+    }
+    for i in 8..15 {
+      self.set_function(8, size - 15 + i, bit(i));
+    }
+    // A module which is always dark.
+    self.set_function(8, size - 8, true);
+  }
+
+  /// Draws both copies of the version information, which only versions 7 and up have.
+  fn draw_version_bits(&mut self) {
+    if self.version < 7 {
+      return;
+    }
+    let mut rem = self.version as u32;
+    for _ in 0..12 {
+      rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+    let bits = (self.version as u32) << 12 | rem;
+    for i in 0..18 {
+      let dark = (bits >> i) & 1 != 0;
+      let a = self.size - 11 + i % 3;
+      let b = i / 3;
+      self.set_function(a, b, dark);
+      self.set_function(b, a, dark);
+    }
+  }
+
+  /// Draws the codewords into the modules that are not part of function patterns, in the zigzag
+  /// order of pairs of columns from the bottom-right.
+  fn draw_codewords(&mut self, data: &[u8]) {
+    let total_bits = data.len() * 8;
+    let mut i = 0;
+    let mut right = self.size - 1;
+    while right >= 1 {
+      // The vertical timing pattern is skipped over.
+      if right == 6 {
+        right = 5;
+      }
+      let upward = (right + 1) & 2 == 0;
+      for vert in 0..self.size {
+        for j in 0..2 {
+          let x = right - j;
+          let y = if upward { self.size - 1 - vert } else { vert };
+          if !self.function.get(x, y) && i < total_bits {
+            self.modules.set(x, y, (data[i >> 3] >> (7 - (i & 7))) & 1 != 0);
+            i += 1;
+          }
+          // Any remaining modules are left light, as the remainder bits.
+        }
+      }
+      right -= 2;
+    }
+  }
+
+  /// Inverts the data modules where the `mask` pattern is set.
+  fn apply_mask(&mut self, mask: u32) {
+    for y in 0..self.size {
+      for x in 0..self.size {
+        let invert = match mask {
+          0 => (x + y) % 2 == 0,
+          1 => y % 2 == 0,
+          2 => x % 3 == 0,
+          3 => (x + y) % 3 == 0,
+          4 => (x / 3 + y / 2) % 2 == 0,
+          5 => x * y % 2 + x * y % 3 == 0,
+          6 => (x * y % 2 + x * y % 3) % 2 == 0,
+          7 => ((x + y) % 2 + x * y % 3) % 2 == 0,
+          _ => unreachable!(),
+        };
+        if invert && !self.function.get(x, y) {
+          self.modules.set(x, y, !self.modules.get(x, y));
+        }
+      }
+    }
+  }
+
+  /// Scores how hard the modules would be to read, with the penalty rules of the QR code standard.
+  /// Lower is better.
+  fn penalty_score(&self) -> i32 {
+    const N1: i32 = 3;
+    const N2: i32 = 3;
+    const N3: i32 = 40;
+    const N4: i32 = 10;
+    let size = self.size;
+    let mut result = 0;
+
+    // Runs of the same color in rows and columns, and patterns that look like finder patterns.
+    for horizontal in [true, false] {
+      for a in 0..size {
+        let mut history = RunHistory::new(size);
+        let mut run_color = false;
+        let mut run_len = 0;
+        for b in 0..size {
+          let dark = if horizontal {
+            self.modules.get(b, a)
+          } else {
+            self.modules.get(a, b)
+          };
+          if dark == run_color {
+            run_len += 1;
+            if run_len == 5 {
+              result += N1;
+            } else if run_len > 5 {
+              result += 1;
+            }
+          } else {
+            history.add_run(run_len);
+            if !run_color {
+              result += history.count_finder_patterns() * N3;
+            }
+            run_color = dark;
+            run_len = 1;
+          }
+        }
+        result += history.terminate_and_count(run_color, run_len) * N3;
+      }
+    }
+
+    // 2x2 blocks of the same color.
+    for y in 0..size - 1 {
+      for x in 0..size - 1 {
+        let c = self.modules.get(x, y);
+        if c == self.modules.get(x + 1, y)
+          && c == self.modules.get(x, y + 1)
+          && c == self.modules.get(x + 1, y + 1)
+        {
+          result += N2;
+        }
+      }
+    }
+
+    // How far the proportion of dark modules is from half.
+    let dark = self.modules.count_set() as i32;
+    let total = size * size;
+    let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+    result += k * N4;
+    result
+  }
+}
+
+/// The lengths of the last 7 runs of modules in a row or column, most recent first, for finding
+/// patterns that look like a finder pattern.
+struct RunHistory {
+  size: i32,
+  runs: [i32; 7],
+}
+impl RunHistory {
+  fn new(size: i32) -> Self {
+    RunHistory { size, runs: [0; 7] }
+  }
+
+  fn add_run(&mut self, mut len: i32) {
+    // The light quiet zone counts as part of the first run.
+    if self.runs[0] == 0 {
+      len += self.size;
+    }
+    self.runs.copy_within(0..6, 1);
+    self.runs[0] = len;
+  }
+
+  /// The number of light-dark-light-dark-dark-dark-dark-light-dark-light patterns, in the ratio
+  /// 1:1:3:1:1 with 4 light modules on one side, that end at the most recent run.
+  fn count_finder_patterns(&self) -> i32 {
+    let r = &self.runs;
+    let n = r[1];
+    let core = n > 0 && r[2] == n && r[3] == n * 3 && r[4] == n && r[5] == n;
+    (core && r[0] >= n * 4 && r[6] >= n) as i32 + (core && r[6] >= n * 4 && r[0] >= n) as i32
+  }
+
+  /// Ends the row or column with the light quiet zone, and counts the finder-like patterns.
+  fn terminate_and_count(&mut self, run_color: bool, mut run_len: i32) -> i32 {
+    if run_color {
+      self.add_run(run_len);
+      run_len = 0;
+    }
+    run_len += self.size;
+    self.add_run(run_len);
+    self.count_finder_patterns()
+  }
+}
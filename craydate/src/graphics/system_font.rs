@@ -0,0 +1,60 @@
+use alloc::boxed::Box;
+
+use super::font::Font;
+use crate::capi_state::CApiState;
+use crate::error::Error;
+
+/// Fonts that are built into the Playdate system, in its `/System/Fonts` folder.
+///
+/// These fonts can be loaded with `Font::system()` without shipping them in the game's pdx.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SystemFont {
+  /// Asheville Sans 14 Light, which is the font used to draw text when no other font is set.
+  AshevilleSans14Light,
+  /// Asheville Sans 14 Bold.
+  AshevilleSans14Bold,
+  /// Asheville Sans 24 Light.
+  AshevilleSans24Light,
+  /// Roobert 10 Bold.
+  Roobert10Bold,
+  /// Roobert 11 Medium.
+  Roobert11Medium,
+  /// Roobert 20 Medium.
+  Roobert20Medium,
+  /// Roobert 24 Medium.
+  Roobert24Medium,
+}
+impl SystemFont {
+  /// The font that Playdate uses to draw text when no other font is set.
+  pub const DEFAULT: SystemFont = SystemFont::AshevilleSans14Light;
+
+  /// The path to the font's file in the Playdate system.
+  pub fn path(self) -> &'static str {
+    match self {
+      SystemFont::AshevilleSans14Light => "/System/Fonts/Asheville-Sans-14-Light.pft",
+      SystemFont::AshevilleSans14Bold => "/System/Fonts/Asheville-Sans-14-Bold.pft",
+      SystemFont::AshevilleSans24Light => "/System/Fonts/Asheville-Sans-24-Light.pft",
+      SystemFont::Roobert10Bold => "/System/Fonts/Roobert-10-Bold.pft",
+      SystemFont::Roobert11Medium => "/System/Fonts/Roobert-11-Medium.pft",
+      SystemFont::Roobert20Medium => "/System/Fonts/Roobert-20-Medium.pft",
+      SystemFont::Roobert24Medium => "/System/Fonts/Roobert-24-Medium.pft",
+    }
+  }
+}
+
+impl Font {
+  /// Returns the built-in system font `font`, loading it the first time it is requested.
+  ///
+  /// System fonts stay loaded for the rest of the program once loaded, so they can be set active
+  /// with `Graphics::set_font()` without needing to be stored by the game.
+  pub fn system(font: SystemFont) -> Result<&'static Font, Error> {
+    let state = CApiState::get();
+    if let Some(f) = state.system_fonts.borrow().get(&font) {
+      return Ok(f);
+    }
+    // Fonts are not freed when a `Font` is dropped, so leaking the `Font` only leaks the pointer.
+    let f: &'static Font = Box::leak(Box::new(Font::from_file(font.path())?));
+    state.system_fonts.borrow_mut().insert(font, f);
+    Ok(f)
+  }
+}
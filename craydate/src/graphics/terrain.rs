@@ -0,0 +1,332 @@
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+
+use super::bitmap::{Bitmap, BitmapRef};
+use super::color::PixelColor;
+use super::graphics::Graphics;
+use crate::ctypes::*;
+
+/// Destructible terrain, such as the ground in a Worms-style game, which can have areas carved out
+/// of it or added to it.
+///
+/// The terrain is drawn from a `Bitmap` whose mask is opaque where the terrain is solid, so carved
+/// out areas show whatever is behind the terrain. Alongside the `Bitmap`, the terrain keeps its own
+/// array of bits for which pixels are solid, so that collision queries do not have to read from the
+/// bitmap's mask. Each operation writes to both, one row span at a time, so they always agree.
+///
+/// The area changed by each operation is accumulated, and can be retrieved with
+/// `take_changed_rect()` to update only what is needed, such as by re-tracing a `Contour` of the
+/// terrain there.
+#[derive(Debug)]
+pub struct Terrain {
+  bitmap: Bitmap,
+  width: i32,
+  height: i32,
+  // One bit per pixel, set when the pixel is solid. Each row starts on a new word.
+  solid: Vec<u32>,
+  words_per_row: usize,
+  changed: Option<euclid::default::Box2D<i32>>,
+}
+impl Terrain {
+  /// Constructs a terrain of `width` by `height` pixels, which is entirely empty.
+  pub fn new(width: i32, height: i32) -> Terrain {
+    assert!(width > 0 && height > 0);
+    let mut bitmap = Bitmap::new(width, height, SolidColor::kColorWhite);
+    let mask = Bitmap::new(width, height, SolidColor::kColorBlack);
+    // The mask is the same size as the bitmap, so this can not fail.
+    bitmap.set_mask_bitmap(&mask).unwrap();
+    let words_per_row = (width as usize).div_ceil(32);
+    Terrain {
+      bitmap,
+      width,
+      height,
+      solid: alloc::vec![0; words_per_row * height as usize],
+      words_per_row,
+      changed: None,
+    }
+  }
+
+  /// Constructs a terrain from a copy of the `image`.
+  ///
+  /// The terrain is solid where the `image`'s mask is opaque. If the `image` has no mask, then the
+  /// terrain is solid everywhere.
+  pub fn from_bitmap(image: &BitmapRef) -> Terrain {
+    let data = image.data();
+    let (width, height) = (data.width(), data.height());
+    assert!(width > 0 && height > 0);
+    let mut bitmap = image.to_owned();
+    if bitmap.mask_bitmap().is_none() {
+      let mask = Bitmap::new(width, height, SolidColor::kColorWhite);
+      // The mask is the same size as the bitmap, so this can not fail.
+      bitmap.set_mask_bitmap(&mask).unwrap();
+    }
+    let words_per_row = (width as usize).div_ceil(32);
+    let mut terrain = Terrain {
+      bitmap,
+      width,
+      height,
+      solid: alloc::vec![0; words_per_row * height as usize],
+      words_per_row,
+      changed: None,
+    };
+
+    let mask = terrain.bitmap.mask_bitmap().unwrap();
+    let pixels = mask.as_pixels();
+    for y in 0..height {
+      for x in 0..width {
+        if pixels.get(x as usize, y as usize) == PixelColor::WHITE {
+          let (index, bit) = Self::bit_index(terrain.words_per_row, x, y);
+          terrain.solid[index] |= bit;
+        }
+      }
+    }
+    terrain.mark_changed(0, 0, width, height);
+    terrain
+  }
+
+  /// The width of the terrain in pixels.
+  pub fn width(&self) -> i32 {
+    self.width
+  }
+  /// The height of the terrain in pixels.
+  pub fn height(&self) -> i32 {
+    self.height
+  }
+  /// The bitmap holding the terrain's image, with a mask that is opaque where the terrain is solid.
+  pub fn bitmap(&self) -> &Bitmap {
+    &self.bitmap
+  }
+  /// Draws the terrain with its upper-left corner at (`x`, `y`).
+  pub fn draw(&self, graphics: &mut Graphics, x: i32, y: i32) {
+    graphics.draw_bitmap(&self.bitmap, x, y, BitmapFlip::kBitmapUnflipped);
+  }
+
+  /// Removes a circle of terrain centered at `center`, with the given `radius`.
+  pub fn carve_circle(&mut self, center: euclid::default::Point2D<i32>, radius: i32) {
+    self.fill_circle(center, radius, None)
+  }
+  /// Removes the terrain inside the polygon made from `points`.
+  ///
+  /// The polygon is closed by connecting the last point back to the first, and areas inside it are
+  /// found with the even-odd rule.
+  pub fn carve_polygon(&mut self, points: &[euclid::default::Point2D<i32>]) {
+    self.fill_polygon(points, None)
+  }
+  /// Adds a circle of solid terrain centered at `center`, with the given `radius`, drawn in the
+  /// `color`.
+  pub fn add_circle(
+    &mut self,
+    center: euclid::default::Point2D<i32>,
+    radius: i32,
+    color: PixelColor,
+  ) {
+    self.fill_circle(center, radius, Some(color))
+  }
+  /// Adds solid terrain inside the polygon made from `points`, drawn in the `color`.
+  ///
+  /// The polygon is closed by connecting the last point back to the first, and areas inside it are
+  /// found with the even-odd rule.
+  pub fn add_polygon(&mut self, points: &[euclid::default::Point2D<i32>], color: PixelColor) {
+    self.fill_polygon(points, Some(color))
+  }
+
+  /// Returns whether the pixel at (`x`, `y`) is solid. Pixels outside the terrain are not solid.
+  pub fn is_solid(&self, x: i32, y: i32) -> bool {
+    if x < 0 || y < 0 || x >= self.width || y >= self.height {
+      return false;
+    }
+    let (index, bit) = Self::bit_index(self.words_per_row, x, y);
+    self.solid[index] & bit != 0
+  }
+  /// Returns whether any pixel inside the `rect` is solid.
+  pub fn is_any_solid_in_rect(&self, rect: euclid::default::Rect<i32>) -> bool {
+    let mut y_range = rect.min_y().max(0)..rect.max_y().min(self.height);
+    let x_range = rect.min_x().max(0)..rect.max_x().min(self.width);
+    y_range.any(|y| x_range.clone().any(|x| self.is_solid(x, y)))
+  }
+  /// Returns the y position of the first solid pixel in column `x`, looking down from `y` and
+  /// including `y` itself.
+  ///
+  /// This is where an object falling from (`x`, `y`) would land. Returns None if there is no solid
+  /// terrain below.
+  pub fn first_solid_below(&self, x: i32, y: i32) -> Option<i32> {
+    (y.max(0)..self.height).find(|y| self.is_solid(x, *y))
+  }
+
+  /// Removes all pieces of solid terrain that are not connected to the bottom edge of the terrain,
+  /// such as islands left floating in the air after carving.
+  ///
+  /// Pixels are connected to their neighbours above, below, left and right. Returns the number of
+  /// pixels that were removed.
+  pub fn remove_unsupported(&mut self) -> usize {
+    // Flood fill from every solid pixel on the bottom row, marking the solid pixels reached. Pixels
+    // are marked when they are pushed, so each pixel is on the stack at most once.
+    let mut supported = alloc::vec![0u32; self.solid.len()];
+    let mut stack = Vec::new();
+    let mut visit = |x: i32, y: i32, stack: &mut Vec<(i32, i32)>| {
+      if self.is_solid(x, y) {
+        let (index, bit) = Self::bit_index(self.words_per_row, x, y);
+        if supported[index] & bit == 0 {
+          supported[index] |= bit;
+          stack.push((x, y));
+        }
+      }
+    };
+    for x in 0..self.width {
+      visit(x, self.height - 1, &mut stack);
+    }
+    while let Some((x, y)) = stack.pop() {
+      visit(x - 1, y, &mut stack);
+      visit(x + 1, y, &mut stack);
+      visit(x, y - 1, &mut stack);
+      visit(x, y + 1, &mut stack);
+    }
+
+    let mut removed = 0;
+    for y in 0..self.height {
+      let mut x = 0;
+      while x < self.width {
+        // Find runs of solid but unsupported pixels, and remove each run as one span.
+        let unsupported = |x: i32| {
+          let (index, bit) = Self::bit_index(self.words_per_row, x, y);
+          self.solid[index] & bit != 0 && supported[index] & bit == 0
+        };
+        if !unsupported(x) {
+          x += 1;
+          continue;
+        }
+        let start = x;
+        while x < self.width && unsupported(x) {
+          x += 1;
+        }
+        removed += (x - start) as usize;
+        self.fill_span(y, start, x, None);
+      }
+    }
+    removed
+  }
+
+  /// Returns the area of the terrain changed since the last call, or None if nothing has changed.
+  ///
+  /// A newly constructed terrain from `from_bitmap()` is changed everywhere.
+  pub fn take_changed_rect(&mut self) -> Option<euclid::default::Rect<i32>> {
+    self.changed.take().map(|b| b.to_rect())
+  }
+
+  fn bit_index(words_per_row: usize, x: i32, y: i32) -> (usize, u32) {
+    (
+      y as usize * words_per_row + x as usize / 32,
+      1 << (x as usize % 32),
+    )
+  }
+
+  fn mark_changed(&mut self, x: i32, y: i32, width: i32, height: i32) {
+    let b =
+      euclid::default::Box2D::new(euclid::point2(x, y), euclid::point2(x + width, y + height));
+    self.changed = Some(match self.changed {
+      Some(c) => c.union(&b),
+      None => b,
+    });
+  }
+
+  fn fill_circle(
+    &mut self,
+    center: euclid::default::Point2D<i32>,
+    radius: i32,
+    fill: Option<PixelColor>,
+  ) {
+    if radius < 0 {
+      return;
+    }
+    // Walk rows from the center outward, while shrinking the half-width of the circle to stay
+    // inside the radius, so no square roots are needed.
+    let mut half_width = radius;
+    for dy in 0..=radius {
+      while half_width * half_width + dy * dy > radius * radius {
+        half_width -= 1;
+      }
+      let (x0, x1) = (center.x - half_width, center.x + half_width + 1);
+      self.fill_span(center.y + dy, x0, x1, fill);
+      if dy != 0 {
+        self.fill_span(center.y - dy, x0, x1, fill);
+      }
+    }
+  }
+
+  fn fill_polygon(&mut self, points: &[euclid::default::Point2D<i32>], fill: Option<PixelColor>) {
+    if points.len() < 3 {
+      return;
+    }
+    let min_y = points.iter().map(|p| p.y).min().unwrap().max(0);
+    let max_y = points.iter().map(|p| p.y).max().unwrap().min(self.height);
+
+    let mut crossings = Vec::new();
+    for y in min_y..max_y {
+      // Pixels are filled when their center is inside the polygon, so intersect each row's center
+      // line with the edges of the polygon.
+      let center_y = y as f32 + 0.5;
+      crossings.clear();
+      for i in 0..points.len() {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        let (a_y, b_y) = (a.y as f32, b.y as f32);
+        if (a_y <= center_y) != (b_y <= center_y) {
+          let t = (center_y - a_y) / (b_y - a_y);
+          crossings.push(a.x as f32 + t * (b.x - a.x) as f32);
+        }
+      }
+      crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+      for pair in crossings.chunks_exact(2) {
+        // The pixels whose centers fall between the crossings.
+        let x0 = ceil_to_i32(pair[0] - 0.5);
+        let x1 = ceil_to_i32(pair[1] - 0.5);
+        self.fill_span(y, x0, x1, fill);
+      }
+    }
+  }
+
+  /// Sets the pixels in row `y` from `x0` up to but not including `x1` to be solid with the color
+  /// `fill`, or to be empty if `fill` is None.
+  fn fill_span(&mut self, y: i32, x0: i32, x1: i32, fill: Option<PixelColor>) {
+    let (x0, x1) = (x0.max(0), x1.min(self.width));
+    if y < 0 || y >= self.height || x0 >= x1 {
+      return;
+    }
+    for x in x0..x1 {
+      let (index, bit) = Self::bit_index(self.words_per_row, x, y);
+      match fill {
+        Some(_) => self.solid[index] |= bit,
+        None => self.solid[index] &= !bit,
+      }
+    }
+
+    let mask_color = if fill.is_some() {
+      PixelColor::WHITE
+    } else {
+      PixelColor::BLACK
+    };
+    let mut mask = self.bitmap.mask_bitmap().unwrap();
+    let mut mask_pixels = mask.as_pixels_mut();
+    for x in x0..x1 {
+      mask_pixels.set(x as usize, y as usize, mask_color);
+    }
+    if let Some(color) = fill {
+      let mut pixels = self.bitmap.as_pixels_mut();
+      for x in x0..x1 {
+        pixels.set(x as usize, y as usize, color);
+      }
+    }
+
+    self.mark_changed(x0, y, x1 - x0, 1);
+  }
+}
+
+/// Rounds `f` up to the nearest integer.
+fn ceil_to_i32(f: f32) -> i32 {
+  // Casting rounds towards zero, which rounds up already for negative values.
+  let truncated = f as i32;
+  if (truncated as f32) < f {
+    truncated + 1
+  } else {
+    truncated
+  }
+}
@@ -0,0 +1,232 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A grid of cells which are each a single bit, such as a map of which tiles are solid, explored or
+/// changed.
+///
+/// This uses an eighth of the memory of a `bool` per cell. The bits are laid out like the pixels of
+/// the display's frame buffer: each byte holds 8 cells, where the highest bit is the leftmost cell,
+/// and each row is padded to a multiple of 4 bytes. So rows can be copied directly to or from the
+/// bytes of a `Bitmap` of the same width, where a set bit is a white pixel.
+///
+/// Cells outside the grid are never read or written: `get()` returns false for them, and writes to
+/// them are ignored, like drawing outside a bitmap.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+  width: i32,
+  height: i32,
+  row_bytes: i32,
+  bytes: Vec<u8>,
+}
+impl BitGrid {
+  /// Constructs a grid of `width` by `height` cells, which are all unset.
+  pub fn new(width: i32, height: i32) -> Self {
+    assert!(width >= 0 && height >= 0);
+    let row_bytes = (width as usize).div_ceil(32) as i32 * 4;
+    BitGrid {
+      width,
+      height,
+      row_bytes,
+      bytes: vec![0; row_bytes as usize * height as usize],
+    }
+  }
+
+  /// The number of columns of cells in the grid.
+  pub fn width(&self) -> i32 {
+    self.width
+  }
+  /// The number of rows of cells in the grid.
+  pub fn height(&self) -> i32 {
+    self.height
+  }
+  /// The number of bytes in each row of the grid, which is padded to a multiple of 4.
+  pub fn row_bytes(&self) -> i32 {
+    self.row_bytes
+  }
+  /// Whether the cell at (`x`, `y`) is inside the grid.
+  pub fn contains(&self, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < self.width && y < self.height
+  }
+
+  /// Returns whether the cell at (`x`, `y`) is set. Cells outside the grid are never set.
+  pub fn get(&self, x: i32, y: i32) -> bool {
+    if !self.contains(x, y) {
+      return false;
+    }
+    let byte = self.bytes[self.byte_index(x, y)];
+    byte & (0x80 >> (x % 8)) != 0
+  }
+  /// Sets or unsets the cell at (`x`, `y`).
+  pub fn set(&mut self, x: i32, y: i32, value: bool) {
+    if !self.contains(x, y) {
+      return;
+    }
+    let index = self.byte_index(x, y);
+    let bit = 0x80 >> (x % 8);
+    if value {
+      self.bytes[index] |= bit;
+    } else {
+      self.bytes[index] &= !bit;
+    }
+  }
+
+  /// Sets or unsets every cell in the grid.
+  pub fn fill(&mut self, value: bool) {
+    self.bytes.fill(if value { 0xff } else { 0 });
+    // Keep the padding bits unset, so that the bytes can be compared and hashed.
+    if value {
+      for y in 0..self.height {
+        self.clear_padding(y);
+      }
+    }
+  }
+  /// Sets or unsets every cell in `rect`.
+  pub fn fill_rect(&mut self, rect: euclid::default::Rect<i32>, value: bool) {
+    if let Some(r) = rect.intersection(&self.bounds()) {
+      for y in r.min_y()..r.max_y() {
+        let row = self.row_mut(y);
+        let mut x = r.min_x();
+        while x < r.max_x() {
+          let (index, mask, count) = byte_span(x, r.max_x());
+          if value {
+            row[index] |= mask;
+          } else {
+            row[index] &= !mask;
+          }
+          x += count;
+        }
+      }
+    }
+  }
+  /// Copies the cells in `src_rect` of `src` into this grid, with the top-left cell of `src_rect`
+  /// placed at `dest`.
+  pub fn copy_rect_from(
+    &mut self,
+    src: &BitGrid,
+    src_rect: euclid::default::Rect<i32>,
+    dest: euclid::default::Point2D<i32>,
+  ) {
+    let src_size = src.bounds().size;
+    let dest_size = self.bounds().size;
+    if let Some((r, d)) = super::clip_copy(src_size, src_rect, dest_size, dest) {
+      for row in 0..r.height() {
+        let from = src.row(r.min_y() + row);
+        let to = self.row_mut(d.y + row);
+        let mut x = d.x;
+        while x < d.x + r.width() {
+          let (index, mask, count) = byte_span(x, d.x + r.width());
+          // Read the 8 cells from the source starting at the matching position, and shift them to
+          // line up with `x` in its byte.
+          let bits = read_byte(from, r.min_x() + (x - d.x)) >> (x % 8);
+          to[index] = (to[index] & !mask) | (bits & mask);
+          x += count;
+        }
+      }
+    }
+  }
+
+  /// Sets each cell that is set in `other`, which must be the same size.
+  pub fn union_with(&mut self, other: &BitGrid) {
+    self.combine(other, |a, b| a | b)
+  }
+  /// Unsets each cell that is not set in `other`, which must be the same size.
+  pub fn intersect_with(&mut self, other: &BitGrid) {
+    self.combine(other, |a, b| a & b)
+  }
+  /// Unsets each cell that is set in `other`, which must be the same size.
+  pub fn subtract(&mut self, other: &BitGrid) {
+    self.combine(other, |a, b| a & !b)
+  }
+  fn combine(&mut self, other: &BitGrid, f: impl Fn(u8, u8) -> u8) {
+    assert_eq!((self.width, self.height), (other.width, other.height));
+    for (a, b) in self.bytes.iter_mut().zip(&other.bytes) {
+      *a = f(*a, *b);
+    }
+  }
+
+  /// Returns the number of cells that are set.
+  pub fn count_set(&self) -> usize {
+    (0..self.height).map(|y| self.count_set_in_row(y, 0, self.width)).sum()
+  }
+  /// Returns whether any cell in `rect` is set.
+  pub fn any_in_rect(&self, rect: euclid::default::Rect<i32>) -> bool {
+    match rect.intersection(&self.bounds()) {
+      Some(r) => (r.min_y()..r.max_y()).any(|y| self.count_set_in_row(y, r.min_x(), r.max_x()) > 0),
+      None => false,
+    }
+  }
+  fn count_set_in_row(&self, y: i32, start: i32, end: i32) -> usize {
+    let row = self.row(y);
+    let mut count = 0;
+    let mut x = start;
+    while x < end {
+      let (index, mask, n) = byte_span(x, end);
+      count += (row[index] & mask).count_ones() as usize;
+      x += n;
+    }
+    count
+  }
+
+  /// Gives read access to the bytes of the row `y`.
+  pub fn row(&self, y: i32) -> &[u8] {
+    assert!(y >= 0 && y < self.height);
+    let start = self.byte_index(0, y);
+    &self.bytes[start..start + self.row_bytes as usize]
+  }
+  /// Gives read-write access to the bytes of the row `y`.
+  ///
+  /// The bits past the `width()` of the row are padding, and should be left unset.
+  pub fn row_mut(&mut self, y: i32) -> &mut [u8] {
+    assert!(y >= 0 && y < self.height);
+    let start = self.byte_index(0, y);
+    &mut self.bytes[start..start + self.row_bytes as usize]
+  }
+  /// Gives read access to all the bytes in the grid, one row after another.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+  /// Gives read-write access to all the bytes in the grid, one row after another.
+  ///
+  /// The bits past the `width()` of each row are padding, and should be left unset.
+  pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+    &mut self.bytes
+  }
+
+  fn bounds(&self) -> euclid::default::Rect<i32> {
+    euclid::default::Rect::from_size(euclid::size2(self.width, self.height))
+  }
+  fn byte_index(&self, x: i32, y: i32) -> usize {
+    y as usize * self.row_bytes as usize + x as usize / 8
+  }
+  fn clear_padding(&mut self, y: i32) {
+    let (width, row_bytes) = (self.width, self.row_bytes);
+    let row = self.row_mut(y);
+    let mut x = width;
+    while x < row_bytes * 8 {
+      let (index, mask, count) = byte_span(x, row_bytes * 8);
+      row[index] &= !mask;
+      x += count;
+    }
+  }
+}
+
+/// Returns the byte holding cell `x` in its row, a mask of the bits in that byte from `x` up to (but
+/// not including) `end`, and the number of bits in the mask.
+fn byte_span(x: i32, end: i32) -> (usize, u8, i32) {
+  let offset = x % 8;
+  let count = (8 - offset).min(end - x);
+  let mask = ((0xff00u16 >> count) as u8) >> offset;
+  (x as usize / 8, mask, count)
+}
+
+/// Returns the 8 cells of `row` starting at cell `x`, where the highest bit is cell `x`. Cells past
+/// the end of the row are unset.
+fn read_byte(row: &[u8], x: i32) -> u8 {
+  let index = x as usize / 8;
+  let shift = x % 8;
+  let high = row[index] << shift;
+  match row.get(index + 1) {
+    Some(next) if shift > 0 => high | (next >> (8 - shift)),
+    _ => high,
+  }
+}
@@ -0,0 +1,139 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::bit_grid::BitGrid;
+
+/// A grid of cells which each hold a small `Copy` value, such as a tile index or a distance.
+///
+/// The cells are stored one row after another in a single allocation, so whole rows can be read or
+/// written as slices.
+///
+/// Cells outside the grid are never read or written: `get()` returns None for them, and writes to
+/// them are ignored, like drawing outside a bitmap.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteGrid<T: Copy> {
+  width: i32,
+  height: i32,
+  cells: Vec<T>,
+}
+impl<T: Copy> ByteGrid<T> {
+  /// Constructs a grid of `width` by `height` cells, which each start with `value`.
+  pub fn new(width: i32, height: i32, value: T) -> Self {
+    assert!(width >= 0 && height >= 0);
+    ByteGrid {
+      width,
+      height,
+      cells: vec![value; width as usize * height as usize],
+    }
+  }
+
+  /// Constructs a grid the same size as `bits`, where each cell is `set` if the matching cell in
+  /// `bits` is set, and `unset` otherwise.
+  ///
+  /// This can turn a map of floors and walls into a grid of tiles to draw, for example.
+  pub fn from_bit_grid(bits: &BitGrid, set: T, unset: T) -> Self {
+    let mut grid = ByteGrid::new(bits.width(), bits.height(), unset);
+    for y in 0..bits.height() {
+      for (x, cell) in grid.row_mut(y).iter_mut().enumerate() {
+        if bits.get(x as i32, y) {
+          *cell = set;
+        }
+      }
+    }
+    grid
+  }
+
+  /// The number of columns of cells in the grid.
+  pub fn width(&self) -> i32 {
+    self.width
+  }
+  /// The number of rows of cells in the grid.
+  pub fn height(&self) -> i32 {
+    self.height
+  }
+  /// Whether the cell at (`x`, `y`) is inside the grid.
+  pub fn contains(&self, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < self.width && y < self.height
+  }
+
+  /// Returns the value of the cell at (`x`, `y`), or None if it is outside the grid.
+  pub fn get(&self, x: i32, y: i32) -> Option<T> {
+    if self.contains(x, y) {
+      Some(self.cells[self.index(x, y)])
+    } else {
+      None
+    }
+  }
+  /// Gives read-write access to the cell at (`x`, `y`), or None if it is outside the grid.
+  pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut T> {
+    if self.contains(x, y) {
+      let index = self.index(x, y);
+      Some(&mut self.cells[index])
+    } else {
+      None
+    }
+  }
+  /// Sets the cell at (`x`, `y`) to `value`.
+  pub fn set(&mut self, x: i32, y: i32, value: T) {
+    if let Some(cell) = self.get_mut(x, y) {
+      *cell = value
+    }
+  }
+
+  /// Sets every cell in the grid to `value`.
+  pub fn fill(&mut self, value: T) {
+    self.cells.fill(value)
+  }
+  /// Sets every cell in `rect` to `value`.
+  pub fn fill_rect(&mut self, rect: euclid::default::Rect<i32>, value: T) {
+    let bounds = euclid::default::Rect::from_size(euclid::size2(self.width, self.height));
+    if let Some(r) = rect.intersection(&bounds) {
+      for y in r.min_y()..r.max_y() {
+        self.row_mut(y)[r.min_x() as usize..r.max_x() as usize].fill(value);
+      }
+    }
+  }
+  /// Copies the cells in `src_rect` of `src` into this grid, with the top-left cell of `src_rect`
+  /// placed at `dest`.
+  pub fn copy_rect_from(
+    &mut self,
+    src: &ByteGrid<T>,
+    src_rect: euclid::default::Rect<i32>,
+    dest: euclid::default::Point2D<i32>,
+  ) {
+    let src_size = euclid::size2(src.width, src.height);
+    let dest_size = euclid::size2(self.width, self.height);
+    if let Some((r, d)) = super::clip_copy(src_size, src_rect, dest_size, dest) {
+      for row in 0..r.height() {
+        let from = &src.row(r.min_y() + row)[r.min_x() as usize..r.max_x() as usize];
+        let to_x = d.x as usize;
+        self.row_mut(d.y + row)[to_x..to_x + from.len()].copy_from_slice(from);
+      }
+    }
+  }
+
+  /// Gives read access to the cells of the row `y`.
+  pub fn row(&self, y: i32) -> &[T] {
+    assert!(y >= 0 && y < self.height);
+    let start = self.index(0, y);
+    &self.cells[start..start + self.width as usize]
+  }
+  /// Gives read-write access to the cells of the row `y`.
+  pub fn row_mut(&mut self, y: i32) -> &mut [T] {
+    assert!(y >= 0 && y < self.height);
+    let start = self.index(0, y);
+    &mut self.cells[start..start + self.width as usize]
+  }
+  /// Gives read access to all the cells, one row after another.
+  pub fn as_slice(&self) -> &[T] {
+    &self.cells
+  }
+  /// Gives read-write access to all the cells, one row after another.
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    &mut self.cells
+  }
+
+  fn index(&self, x: i32, y: i32) -> usize {
+    y as usize * self.width as usize + x as usize
+  }
+}
@@ -0,0 +1,27 @@
+mod bit_grid;
+mod byte_grid;
+
+pub use bit_grid::BitGrid;
+pub use byte_grid::ByteGrid;
+
+/// Clips a copy of `src_rect` from a grid of `src_size` to `dest` in a grid of `dest_size`, so that
+/// both the source and destination are inside their grids.
+///
+/// Returns the source rect and destination point that remain, or None if nothing is left to copy.
+fn clip_copy(
+  src_size: euclid::default::Size2D<i32>,
+  src_rect: euclid::default::Rect<i32>,
+  dest_size: euclid::default::Size2D<i32>,
+  dest: euclid::default::Point2D<i32>,
+) -> Option<(euclid::default::Rect<i32>, euclid::default::Point2D<i32>)> {
+  let src_clipped = src_rect.intersection(&euclid::default::Rect::from_size(src_size))?;
+  // Move the destination by as much as the source was clipped on its top and left.
+  let dest = dest + (src_clipped.origin - src_rect.origin);
+  let dest_rect = euclid::default::Rect::new(dest, src_clipped.size);
+  let dest_clipped = dest_rect.intersection(&euclid::default::Rect::from_size(dest_size))?;
+  let src_origin = src_clipped.origin + (dest_clipped.origin - dest);
+  Some((
+    euclid::default::Rect::new(src_origin, dest_clipped.size),
+    dest_clipped.origin,
+  ))
+}
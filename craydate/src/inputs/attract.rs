@@ -0,0 +1,117 @@
+use super::crank::Crank;
+use super::input_recording::InputRecording;
+use super::inputs::Inputs;
+use crate::time::TimeDelta;
+
+/// A change in the state of an `AttractMode`, returned from `AttractMode::update()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttractEvent {
+  /// The player has been idle for long enough, and playback of the recording has started.
+  ///
+  /// The game should reset to the same state it was in when the recording started, including any
+  /// random seeds, as only the input is recorded. Then it should run as normal, but with the
+  /// `Inputs` from `AttractMode::playback_inputs()`.
+  Started,
+  /// The player pressed a button or moved the crank, which stopped the playback.
+  ///
+  /// The game should return to its title screen. The input which stopped the playback should not be
+  /// acted on, so that pressing a button to stop the demo does not also select something on the
+  /// title screen.
+  Stopped,
+  /// The recording played to its end.
+  ///
+  /// The game should return to its title screen, where the wait for the player to be idle starts
+  /// again.
+  Finished,
+}
+
+/// A demo, or attract mode, for a game's title screen, which plays a recording of the game being
+/// played after the player has been idle for a while.
+///
+/// Call `update()` each frame with the real `Inputs` while the title screen or the demo is shown.
+/// After the player has not touched the buttons or crank for the idle timeout, playback starts, and
+/// `playback_inputs()` gives the recorded `Inputs` to run the game with for each frame. When the
+/// player touches any button or the crank, or the recording ends, playback stops and control
+/// returns to the player.
+#[derive(Debug)]
+pub struct AttractMode {
+  recording: InputRecording,
+  idle_timeout: TimeDelta,
+  idle_time: TimeDelta,
+  // The frame of the recording being played, or None when waiting for the player to be idle.
+  frame: Option<usize>,
+}
+impl AttractMode {
+  /// Constructs an `AttractMode` which plays the `recording` after the player has been idle for
+  /// `idle_timeout`.
+  pub fn new(recording: InputRecording, idle_timeout: TimeDelta) -> Self {
+    AttractMode {
+      recording,
+      idle_timeout,
+      idle_time: TimeDelta::from_milliseconds(0),
+      frame: None,
+    }
+  }
+
+  /// Whether the recording is being played.
+  pub fn is_playing(&self) -> bool {
+    self.frame.is_some()
+  }
+  /// Stops any playback, and restarts the wait for the player to be idle.
+  pub fn reset(&mut self) {
+    self.frame = None;
+    self.idle_time = TimeDelta::from_milliseconds(0);
+  }
+
+  /// Updates the attract mode with the player's `inputs` for a frame that took `dt` time, and
+  /// returns the change in state if there was one.
+  pub fn update(&mut self, inputs: &Inputs, dt: TimeDelta) -> Option<AttractEvent> {
+    let active = is_active(inputs);
+    match self.frame {
+      Some(_) if active => {
+        self.reset();
+        Some(AttractEvent::Stopped)
+      }
+      Some(frame) if frame + 1 >= self.recording.len() => {
+        self.reset();
+        Some(AttractEvent::Finished)
+      }
+      Some(frame) => {
+        self.frame = Some(frame + 1);
+        None
+      }
+      None if active => {
+        self.idle_time = TimeDelta::from_milliseconds(0);
+        None
+      }
+      None => {
+        self.idle_time = TimeDelta::from_milliseconds(
+          self.idle_time.total_whole_milliseconds() + dt.total_whole_milliseconds(),
+        );
+        if self.idle_time >= self.idle_timeout && !self.recording.is_empty() {
+          self.frame = Some(0);
+          Some(AttractEvent::Started)
+        } else {
+          None
+        }
+      }
+    }
+  }
+
+  /// The recorded `Inputs` for the current frame of playback, or None if the recording is not
+  /// playing.
+  pub fn playback_inputs(&self) -> Option<Inputs> {
+    self.recording.inputs(self.frame?)
+  }
+}
+
+/// Whether the player is touching the buttons or crank in `inputs`.
+fn is_active(inputs: &Inputs) -> bool {
+  let set = &inputs.button_state_per_frame()[0];
+  let buttons = set.current.0 | set.pushed.0 | set.released.0;
+  let crank_moved = match inputs.crank() {
+    Crank::Docked => false,
+    Crank::Undocked { change, .. } => *change != 0.0,
+  };
+  buttons != 0 || crank_moved
+}
@@ -0,0 +1,146 @@
+use super::crank::Crank;
+
+/// A direction that the crank can turn in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CrankDirection {
+  /// The crank angle increases when turned clockwise.
+  Clockwise,
+  /// The crank angle decreases when turned counter-clockwise.
+  CounterClockwise,
+}
+impl CrankDirection {
+  /// The other direction.
+  pub fn reversed(self) -> CrankDirection {
+    match self {
+      CrankDirection::Clockwise => CrankDirection::CounterClockwise,
+      CrankDirection::CounterClockwise => CrankDirection::Clockwise,
+    }
+  }
+
+  /// The change in angle, in degrees, in this direction, which is negative for movement in the
+  /// other direction.
+  fn along(self, change: f32) -> f32 {
+    match self {
+      CrankDirection::Clockwise => change,
+      CrankDirection::CounterClockwise => -change,
+    }
+  }
+}
+
+/// A reversal of the crank's direction, which is returned from `CrankDirectionTracker::update()`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CrankReversal {
+  /// The direction the crank is now turning in.
+  pub direction: CrankDirection,
+  /// How far, in degrees, the crank turned in the previous direction before reversing. For a
+  /// sawing or rowing motion, this is the length of the stroke that just ended.
+  pub stroke: f32,
+}
+
+/// Tracks the direction the crank is turning in, and reports when it reverses.
+///
+/// The sign of the crank's change in each frame flips often from small jitters in the player's
+/// hand, even when they mean to keep turning one way. The tracker only counts a reversal once the
+/// crank has moved back from the furthest point of its current stroke by more than the hysteresis,
+/// in degrees. This makes it useful for mechanics such as sawing or rowing, where each stroke back
+/// and forth is an action.
+#[derive(Debug, Clone)]
+pub struct CrankDirectionTracker {
+  hysteresis: f32,
+  direction: Option<CrankDirection>,
+  // How far the crank has moved along `direction` since the stroke began, or in total if there is
+  // no direction yet.
+  travel: f32,
+  // The furthest `travel` has reached in the current stroke.
+  peak: f32,
+}
+impl CrankDirectionTracker {
+  /// Constructs a tracker which counts a reversal once the crank moves back by more than
+  /// `hysteresis` degrees.
+  pub fn new(hysteresis: f32) -> Self {
+    CrankDirectionTracker {
+      hysteresis: hysteresis.max(0.0),
+      direction: None,
+      travel: 0.0,
+      peak: 0.0,
+    }
+  }
+
+  /// The number of degrees the crank must move back before a reversal is counted.
+  pub fn hysteresis(&self) -> f32 {
+    self.hysteresis
+  }
+  /// Sets the number of degrees the crank must move back before a reversal is counted.
+  pub fn set_hysteresis(&mut self, hysteresis: f32) {
+    self.hysteresis = hysteresis.max(0.0)
+  }
+
+  /// The direction the crank is turning in, or None if it is docked or has not yet moved further
+  /// than the hysteresis in either direction.
+  pub fn direction(&self) -> Option<CrankDirection> {
+    self.direction
+  }
+
+  /// Forgets the crank's direction, as if it had not yet moved.
+  pub fn reset(&mut self) {
+    self.direction = None;
+    self.travel = 0.0;
+    self.peak = 0.0;
+  }
+
+  /// Updates the tracker with the state of the `crank` for a frame, which is found in
+  /// `Inputs::crank()`. Returns the reversal if the crank changed direction.
+  ///
+  /// The first direction the crank moves in, after being docked or reset, is not a reversal, but is
+  /// given by `direction()`.
+  pub fn update(&mut self, crank: &Crank) -> Option<CrankReversal> {
+    let change = match crank {
+      Crank::Docked => {
+        self.reset();
+        return None;
+      }
+      Crank::Undocked { change, .. } => *change,
+    };
+
+    let direction = match self.direction {
+      Some(direction) => direction,
+      None => {
+        self.travel += change;
+        if self.travel.abs() > self.hysteresis {
+          let direction = if self.travel > 0.0 {
+            CrankDirection::Clockwise
+          } else {
+            CrankDirection::CounterClockwise
+          };
+          self.direction = Some(direction);
+          self.travel = self.travel.abs();
+          self.peak = self.travel;
+        }
+        return None;
+      }
+    };
+
+    self.travel += direction.along(change);
+    self.peak = self.peak.max(self.travel);
+    let back = self.peak - self.travel;
+    if back > self.hysteresis {
+      let reversal = CrankReversal {
+        direction: direction.reversed(),
+        stroke: self.peak,
+      };
+      self.direction = Some(reversal.direction);
+      // The crank has already moved `back` degrees into the new stroke.
+      self.travel = back;
+      self.peak = back;
+      Some(reversal)
+    } else {
+      None
+    }
+  }
+}
+impl Default for CrankDirectionTracker {
+  /// Constructs a tracker with a hysteresis of 5 degrees.
+  fn default() -> Self {
+    CrankDirectionTracker::new(5.0)
+  }
+}
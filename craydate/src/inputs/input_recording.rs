@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use super::crank::Crank;
+use super::inputs::Inputs;
+use crate::ctypes::*;
+use crate::error::Error;
+
+/// The header at the start of the bytes of an `InputRecording`.
+const MAGIC: &[u8; 4] = b"CDIR";
+/// The number of bytes for each frame in the bytes of an `InputRecording`.
+const FRAME_BYTES: usize = 12;
+
+/// The input from a single frame of an `InputRecording`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RecordedFrame {
+  // The bitmasks of the buttons from the frame's `PDButtonsSet`.
+  current: u8,
+  pushed: u8,
+  released: u8,
+  crank: Crank,
+}
+impl RecordedFrame {
+  fn button_set(&self) -> PDButtonsSet {
+    PDButtonsSet {
+      current: CButtons(self.current as _),
+      pushed: CButtons(self.pushed as _),
+      released: CButtons(self.released as _),
+    }
+  }
+}
+
+/// Records the button and crank input of each frame, to be played back later as an
+/// `InputRecording`.
+#[derive(Debug, Default)]
+pub struct InputRecorder {
+  frames: Vec<RecordedFrame>,
+}
+impl InputRecorder {
+  /// Constructs a recorder with no frames recorded.
+  pub fn new() -> Self {
+    InputRecorder::default()
+  }
+
+  /// Records the `inputs` of a frame. This should be called once for each frame.
+  pub fn record(&mut self, inputs: &Inputs) {
+    let set = &inputs.button_state_per_frame()[0];
+    self.frames.push(RecordedFrame {
+      current: set.current.0 as u8,
+      pushed: set.pushed.0 as u8,
+      released: set.released.0 as u8,
+      crank: *inputs.crank(),
+    });
+  }
+
+  /// The number of frames recorded so far.
+  pub fn len(&self) -> usize {
+    self.frames.len()
+  }
+  /// Whether no frames have been recorded.
+  pub fn is_empty(&self) -> bool {
+    self.frames.is_empty()
+  }
+
+  /// Finishes recording, and returns the recorded frames.
+  pub fn finish(self) -> InputRecording {
+    InputRecording {
+      frames: self.frames,
+    }
+  }
+}
+
+/// The button and crank input of a sequence of frames, which can be played back to the game as the
+/// `Inputs` of each frame.
+///
+/// The accelerometer is not recorded, so it is not available in the played back `Inputs`.
+///
+/// A recording can be saved with `to_bytes()` and `File::write_file()`, which writes to the game's
+/// Data folder. A recording made in the simulator can then be copied from the Data folder into the
+/// game's assets, so that it is part of the pdx, and loaded with `File::read_file()` and
+/// `from_bytes()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputRecording {
+  frames: Vec<RecordedFrame>,
+}
+impl InputRecording {
+  /// The number of frames in the recording.
+  pub fn len(&self) -> usize {
+    self.frames.len()
+  }
+  /// Whether the recording has no frames.
+  pub fn is_empty(&self) -> bool {
+    self.frames.is_empty()
+  }
+
+  /// Returns the input of the frame at `index` as `Inputs`, as they were when recorded, or None if
+  /// the index is past the end of the recording.
+  ///
+  /// Button events are found by comparing to the frame before, as they are for the device's input.
+  pub fn inputs(&self, index: usize) -> Option<Inputs> {
+    let frame = self.frames.get(index)?;
+    let previous = match index {
+      0 => frame,
+      _ => &self.frames[index - 1],
+    };
+    Some(Inputs::from_parts(
+      Peripherals::kNone,
+      [frame.button_set(), previous.button_set()],
+      frame.crank,
+    ))
+  }
+
+  /// Converts the recording to bytes, which can be saved to a file.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 4 + self.frames.len() * FRAME_BYTES);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+    for frame in &self.frames {
+      let (docked, angle, change) = match frame.crank {
+        Crank::Docked => (1, 0.0f32, 0.0f32),
+        Crank::Undocked { angle, change } => (0, angle, change),
+      };
+      bytes.extend_from_slice(&[frame.current, frame.pushed, frame.released, docked]);
+      bytes.extend_from_slice(&angle.to_le_bytes());
+      bytes.extend_from_slice(&change.to_le_bytes());
+    }
+    bytes
+  }
+  /// Constructs a recording from bytes made by `to_bytes()`.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+    let header = MAGIC.len() + 4;
+    if bytes.len() < header || &bytes[..MAGIC.len()] != MAGIC {
+      return Err(Error::InvalidInputRecording);
+    }
+    let count = u32::from_le_bytes(bytes[MAGIC.len()..header].try_into().unwrap()) as usize;
+    let body = &bytes[header..];
+    if body.len() != count * FRAME_BYTES {
+      return Err(Error::InvalidInputRecording);
+    }
+    let f32_at = |b: &[u8]| f32::from_le_bytes(b.try_into().unwrap());
+    let frames = body
+      .chunks_exact(FRAME_BYTES)
+      .map(|b| RecordedFrame {
+        current: b[0],
+        pushed: b[1],
+        released: b[2],
+        crank: match b[3] {
+          0 => Crank::Undocked {
+            angle: f32_at(&b[4..8]),
+            change: f32_at(&b[8..12]),
+          },
+          _ => Crank::Docked,
+        },
+      })
+      .collect();
+    Ok(InputRecording { frames })
+  }
+}
@@ -10,6 +10,8 @@ use crate::system::System;
 pub struct Inputs {
   peripherals_enabled: Peripherals,
   buttons: Buttons,
+  // The button states that `buttons` was made from, for the current and previous frame.
+  button_state_per_frame: [PDButtonsSet; 2],
   crank: Crank,
 }
 impl Inputs {
@@ -30,13 +32,28 @@ impl Inputs {
       }
     };
 
+    Self::from_parts(peripherals_enabled, *button_state_per_frame, crank)
+  }
+
+  /// Constructs `Inputs` from button states and crank state that were recorded earlier.
+  pub(crate) fn from_parts(
+    peripherals_enabled: Peripherals,
+    button_state_per_frame: [PDButtonsSet; 2],
+    crank: Crank,
+  ) -> Self {
     Inputs {
       peripherals_enabled,
-      buttons: Buttons::new(button_state_per_frame),
+      buttons: Buttons::new(&button_state_per_frame),
+      button_state_per_frame,
       crank,
     }
   }
 
+  /// The button states for the current and previous frame respectively.
+  pub(crate) fn button_state_per_frame(&self) -> &[PDButtonsSet; 2] {
+    &self.button_state_per_frame
+  }
+
   /// Returns the last read values from the accelerometor.
   ///
   /// These values are only present if the accelerometer is enabled via `System::enable_devices()`,
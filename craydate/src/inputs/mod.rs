@@ -1,13 +1,19 @@
+mod attract;
 mod button_event;
 mod button_state;
 mod crank;
+mod crank_direction;
+mod input_recording;
 mod inputs;
 mod button;
 mod buttons;
 
+pub use attract::{AttractEvent, AttractMode};
 pub use button_state::ButtonState;
+pub use input_recording::{InputRecorder, InputRecording};
 pub use inputs::Inputs;
 pub use crank::Crank;
+pub use crank_direction::{CrankDirection, CrankDirectionTracker, CrankReversal};
 pub use button::Button;
 pub use button_event::ButtonEvent;
 pub use buttons::Buttons;
\ No newline at end of file
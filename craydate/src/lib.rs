@@ -217,8 +217,10 @@ extern crate craydate_macro;
 /// ```
 pub use craydate_macro::main;
 
+mod ai;
 mod allocator;
 mod api;
+mod auto_pause;
 mod callback_builder;
 mod callbacks;
 mod capi_state;
@@ -231,10 +233,16 @@ mod executor;
 mod files;
 mod geometry;
 mod graphics;
+mod grid;
 mod inputs;
 mod log;
+mod math;
 mod menu;
 mod null_terminated;
+mod panic_screen;
+mod procgen;
+mod resource_scope;
+mod shutdown;
 mod sound;
 mod system;
 mod system_event;
@@ -248,9 +256,11 @@ pub mod macro_helpers;
 /// `extern crate alloc` elsewhere.
 pub use alloc::{borrow::ToOwned, format, string::String};
 
+pub use ai::*;
 pub use api::*;
+pub use auto_pause::{AutoPause, PauseChange, PauseReason};
 pub use callback_builder::{CallbackBuilder, CallbackBuilderWithArg};
-pub use callbacks::Callbacks;
+pub use callbacks::{CallbackId, CallbackInfo, CallbackKind, Callbacks};
 pub use clamped_float::*;
 pub use ctypes_enums::*;
 pub use display::*;
@@ -258,9 +268,17 @@ pub use error::*;
 pub use files::*;
 pub use geometry::*;
 pub use graphics::*;
+pub use grid::*;
 pub use inputs::*;
 pub use log::{log, log_error};
+pub use math::*;
 pub use menu::*;
+pub use panic_screen::{set_panic_screen, PanicScreen};
+pub use procgen::*;
+pub use resource_scope::{ResourceScope, ScopedHandle};
+pub use shutdown::{
+  on_shutdown, on_shutdown_with_budget, ShutdownReason, DEFAULT_SHUTDOWN_HOOK_BUDGET,
+};
 pub use sound::*;
 pub use system::*;
 pub use system_event::*;
@@ -299,12 +317,17 @@ pub fn panic_handler(panic_info: &core::panic::PanicInfo) -> ! {
     //crate::debug::log_bytes_to_stdout(b"panic has unknown payload");
   }
 
+  // The panic screen is only shown on the device, so this returns, but it keeps the panic screen
+  // built and checked in simulator builds.
+  panic_screen::show_panic_screen(panic_info);
   core::intrinsics::abort()
 }
 
 #[doc(hidden)]
 #[cfg(all(target_arch = "arm", target_os = "none"))]
-pub fn panic_handler(_panic_info: &core::panic::PanicInfo) -> ! {
+pub fn panic_handler(panic_info: &core::panic::PanicInfo) -> ! {
+  // Does not return if a panic screen is shown.
+  panic_screen::show_panic_screen(panic_info);
   core::intrinsics::abort()
 }
 
@@ -85,6 +85,7 @@ pub mod __private {
       CSystemEvent::kEventLock => {
         CApiState::get().add_system_event(SystemEvent::WillLock);
         Executor::wake_system_wakers(CApiState::get().executor);
+        crate::shutdown::run_shutdown_hooks(ShutdownReason::Lock);
       }
       CSystemEvent::kEventLowPower => {
         CApiState::get().add_system_event(SystemEvent::WillSleep);
@@ -101,6 +102,7 @@ pub mod __private {
       CSystemEvent::kEventTerminate => {
         CApiState::get().add_system_event(SystemEvent::WillTerminate);
         Executor::wake_system_wakers(CApiState::get().executor);
+        crate::shutdown::run_shutdown_hooks(ShutdownReason::Terminate);
       }
       CSystemEvent::kEventUnlock => {
         CApiState::get().add_system_event(SystemEvent::DidUnlock);
@@ -139,6 +141,18 @@ pub mod __private {
     };
     capi.set_current_frame_button_state(buttons_set);
 
+    // Report changes to watched data files before the frame, so the game can reload them before it
+    // updates.
+    #[cfg(not(all(target_arch = "arm", target_os = "none")))]
+    {
+      let now_ms = unsafe { capi.csystem.getCurrentTimeMilliseconds.unwrap()() };
+      let changed = capi.data_file_watches.borrow_mut().poll_changes(now_ms);
+      for path in changed {
+        capi.add_system_event(SystemEvent::DataFileChanged(path));
+        Executor::wake_system_wakers(capi.executor);
+      }
+    }
+
     CApiState::get().add_system_event(SystemEvent::NextFrame {
       frame_number: capi.frame_number.get(),
       inputs: Inputs::new(
@@ -0,0 +1,81 @@
+use core::hash::Hasher;
+
+use super::fixed::Fixed;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Builds a checksum of game state, for finding where a replay or lockstep game diverges between
+/// the device and the simulator, or between two devices.
+///
+/// Write the values that make up the game's state each frame, and compare the `finish()` result
+/// with a checksum recorded on the other side. The first frame where they differ is where the
+/// state diverged.
+///
+/// Floats are written by their bits, so a difference of a single bit changes the checksum. Every
+/// NaN is written as the same bits, though, as the bits of a NaN can differ between platforms even
+/// when the computation is the same.
+///
+/// `StateChecksum` is a `Hasher`, so types that implement `Hash` can be written with `hash()`. The
+/// checksum is the 64-bit FNV-1a hash of the bytes written, which does not depend on the platform.
+#[derive(Debug, Clone)]
+pub struct StateChecksum(u64);
+impl StateChecksum {
+  /// Constructs a checksum with no values written.
+  pub fn new() -> Self {
+    StateChecksum(FNV_OFFSET_BASIS)
+  }
+
+  /// Writes an `f32` to the checksum.
+  pub fn write_f32(&mut self, f: f32) {
+    let bits = if f.is_nan() { f32::NAN.to_bits() } else { f.to_bits() };
+    self.write_u32(bits)
+  }
+  /// Writes a `Fixed` to the checksum.
+  pub fn write_fixed(&mut self, f: Fixed) {
+    self.write_i32(f.to_bits())
+  }
+}
+impl Default for StateChecksum {
+  fn default() -> Self {
+    StateChecksum::new()
+  }
+}
+impl Hasher for StateChecksum {
+  fn write(&mut self, bytes: &[u8]) {
+    for b in bytes {
+      self.0 = (self.0 ^ *b as u64).wrapping_mul(FNV_PRIME);
+    }
+  }
+  // The default implementations use native byte order, so they are replaced to give the same
+  // checksum on every platform.
+  fn write_u16(&mut self, i: u16) {
+    self.write(&i.to_le_bytes())
+  }
+  fn write_u32(&mut self, i: u32) {
+    self.write(&i.to_le_bytes())
+  }
+  fn write_u64(&mut self, i: u64) {
+    self.write(&i.to_le_bytes())
+  }
+  fn write_usize(&mut self, i: usize) {
+    // usize is 32 bits on the device, but 64 bits in the simulator.
+    self.write_u64(i as u64)
+  }
+  fn write_i16(&mut self, i: i16) {
+    self.write_u16(i as u16)
+  }
+  fn write_i32(&mut self, i: i32) {
+    self.write_u32(i as u32)
+  }
+  fn write_i64(&mut self, i: i64) {
+    self.write_u64(i as u64)
+  }
+  fn write_isize(&mut self, i: isize) {
+    self.write_u64(i as i64 as u64)
+  }
+
+  fn finish(&self) -> u64 {
+    self.0
+  }
+}
@@ -0,0 +1,232 @@
+/// A fixed-point number with 16 bits for the whole part and 16 bits for the fractional part.
+///
+/// All arithmetic on a `Fixed` is done with integers, so it gives exactly the same results on the
+/// device and in the simulator, regardless of the compiler or math library. This makes it a good
+/// choice for game state that must be reproduced exactly, such as in replays or lockstep
+/// multiplayer.
+///
+/// The range of a `Fixed` is -32768 to just below 32768, with a precision of 1/65536. Addition and
+/// subtraction wrap on overflow, like integers in release builds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fixed(i32);
+impl Fixed {
+  /// The number of bits used for the fractional part.
+  pub const FRAC_BITS: u32 = 16;
+
+  /// The value 0.
+  pub const ZERO: Fixed = Fixed(0);
+  /// The value 1.
+  pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+  /// The value 0.5.
+  pub const HALF: Fixed = Fixed(1 << (Self::FRAC_BITS - 1));
+  /// The smallest value greater than 0.
+  pub const EPSILON: Fixed = Fixed(1);
+  /// The smallest value that can be represented.
+  pub const MIN: Fixed = Fixed(i32::MIN);
+  /// The largest value that can be represented.
+  pub const MAX: Fixed = Fixed(i32::MAX);
+  /// Archimedes' constant (π).
+  pub const PI: Fixed = Fixed(205887);
+  /// π/2.
+  pub const FRAC_PI_2: Fixed = Fixed(102944);
+  /// The full circle constant (τ = 2π).
+  pub const TAU: Fixed = Fixed(411775);
+
+  /// Constructs a `Fixed` from its raw bits, which are the value multiplied by 65536.
+  pub const fn from_bits(bits: i32) -> Self {
+    Fixed(bits)
+  }
+  /// Returns the raw bits of the value, which are the value multiplied by 65536.
+  pub const fn to_bits(self) -> i32 {
+    self.0
+  }
+
+  /// Constructs a `Fixed` from a whole number.
+  pub const fn from_i32(i: i32) -> Self {
+    Fixed(i << Self::FRAC_BITS)
+  }
+  /// Constructs a `Fixed` from the nearest value to the fraction `numerator / denominator`,
+  /// rounding half-way cases away from zero, and saturating at `MIN` and `MAX`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `denominator` is zero.
+  pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+    assert!(
+      denominator != 0,
+      "Fixed::from_ratio with a zero denominator"
+    );
+    let n = (numerator as i64) << Self::FRAC_BITS;
+    let d = denominator as i64;
+    let q = (n.abs() + d.abs() / 2) / d.abs();
+    let q = if (n < 0) != (d < 0) { -q } else { q };
+    if q > i32::MAX as i64 {
+      Self::MAX
+    } else if q < i32::MIN as i64 {
+      Self::MIN
+    } else {
+      Fixed(q as i32)
+    }
+  }
+  /// Constructs a `Fixed` from the nearest value to `f`, saturating at `MIN` and `MAX`.
+  ///
+  /// The conversion is exact for a given `f`, so it is deterministic as long as `f` is. It is
+  /// useful for constants and for values loaded from data files.
+  pub fn from_f32(f: f32) -> Self {
+    Fixed(super::round(f * Self::ONE.0 as f32) as i32)
+  }
+  /// Converts to the nearest `f32`, for drawing or for passing to other APIs.
+  pub fn to_f32(self) -> f32 {
+    self.0 as f32 / Self::ONE.0 as f32
+  }
+
+  /// Returns the largest whole number less than or equal to the value.
+  pub const fn floor(self) -> i32 {
+    self.0 >> Self::FRAC_BITS
+  }
+  /// Returns the smallest whole number greater than or equal to the value.
+  pub const fn ceil(self) -> i32 {
+    ((self.0 as i64 + Self::ONE.0 as i64 - 1) >> Self::FRAC_BITS) as i32
+  }
+  /// Returns the nearest whole number to the value, rounding half-way cases up.
+  pub const fn round(self) -> i32 {
+    ((self.0 as i64 + Self::HALF.0 as i64) >> Self::FRAC_BITS) as i32
+  }
+  /// Returns the fractional part of the value, which is always positive: `self - floor(self)`.
+  pub const fn fract(self) -> Fixed {
+    Fixed(self.0 & (Self::ONE.0 - 1))
+  }
+  /// Returns the absolute value.
+  pub const fn abs(self) -> Fixed {
+    Fixed(self.0.wrapping_abs())
+  }
+  /// Returns the smaller of the two values.
+  pub fn min(self, other: Fixed) -> Fixed {
+    Ord::min(self, other)
+  }
+  /// Returns the larger of the two values.
+  pub fn max(self, other: Fixed) -> Fixed {
+    Ord::max(self, other)
+  }
+  /// Returns the value linearly interpolated from `self` to `other` by `t`, where a `t` of 0 gives
+  /// `self` and a `t` of 1 gives `other`.
+  pub fn lerp(self, other: Fixed, t: Fixed) -> Fixed {
+    self + (other - self) * t
+  }
+
+  /// Returns the square root of the value, rounded down.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the value is negative.
+  pub fn sqrt(self) -> Fixed {
+    assert!(self.0 >= 0, "sqrt of a negative Fixed");
+    Fixed(((self.0 as u64) << Self::FRAC_BITS).isqrt() as i32)
+  }
+
+  /// Returns the sine of the value, in radians.
+  ///
+  /// The result is within about 1/16384 of the exact value.
+  pub fn sin(self) -> Fixed {
+    const PI: i64 = Fixed::PI.0 as i64;
+    const FRAC_PI_2: i64 = Fixed::FRAC_PI_2.0 as i64;
+    const ONE: i64 = Fixed::ONE.0 as i64;
+
+    // Move the angle into -π/2..=π/2, where sin() has the same value.
+    let mut x = (self.0 as i64).rem_euclid(Fixed::TAU.0 as i64);
+    if x > PI {
+      x -= Fixed::TAU.0 as i64;
+    }
+    if x > FRAC_PI_2 {
+      x = PI - x;
+    } else if x < -FRAC_PI_2 {
+      x = -PI - x;
+    }
+
+    // The Taylor series to x⁹, evaluated as x(1 - x²/(2·3)(1 - x²/(4·5)(1 - x²/(6·7)(1 - x²/(8·9))))).
+    let x2 = (x * x) >> Self::FRAC_BITS;
+    let mut t = ONE;
+    for d in [72, 42, 20, 6] {
+      t = ONE - ((x2 * t) >> Self::FRAC_BITS) / d;
+    }
+    Fixed(((x * t) >> Self::FRAC_BITS) as i32)
+  }
+  /// Returns the cosine of the value, in radians.
+  ///
+  /// The result is within about 1/16384 of the exact value.
+  pub fn cos(self) -> Fixed {
+    Fixed((self.0 as i64 + Self::FRAC_PI_2.0 as i64).rem_euclid(Self::TAU.0 as i64) as i32).sin()
+  }
+}
+
+impl From<i32> for Fixed {
+  fn from(i: i32) -> Self {
+    Fixed::from_i32(i)
+  }
+}
+
+impl core::ops::Add for Fixed {
+  type Output = Fixed;
+
+  fn add(self, rhs: Fixed) -> Fixed {
+    Fixed(self.0.wrapping_add(rhs.0))
+  }
+}
+impl core::ops::Sub for Fixed {
+  type Output = Fixed;
+
+  fn sub(self, rhs: Fixed) -> Fixed {
+    Fixed(self.0.wrapping_sub(rhs.0))
+  }
+}
+impl core::ops::Mul for Fixed {
+  type Output = Fixed;
+
+  fn mul(self, rhs: Fixed) -> Fixed {
+    Fixed(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+  }
+}
+impl core::ops::Div for Fixed {
+  type Output = Fixed;
+
+  /// # Panics
+  ///
+  /// Panics if `rhs` is zero.
+  fn div(self, rhs: Fixed) -> Fixed {
+    Fixed((((self.0 as i64) << Self::FRAC_BITS) / rhs.0 as i64) as i32)
+  }
+}
+impl core::ops::Neg for Fixed {
+  type Output = Fixed;
+
+  fn neg(self) -> Fixed {
+    Fixed(self.0.wrapping_neg())
+  }
+}
+impl core::ops::AddAssign for Fixed {
+  fn add_assign(&mut self, rhs: Fixed) {
+    *self = *self + rhs
+  }
+}
+impl core::ops::SubAssign for Fixed {
+  fn sub_assign(&mut self, rhs: Fixed) {
+    *self = *self - rhs
+  }
+}
+impl core::ops::MulAssign for Fixed {
+  fn mul_assign(&mut self, rhs: Fixed) {
+    *self = *self * rhs
+  }
+}
+impl core::ops::DivAssign for Fixed {
+  fn div_assign(&mut self, rhs: Fixed) {
+    *self = *self / rhs
+  }
+}
+
+impl core::fmt::Display for Fixed {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    core::fmt::Display::fmt(&self.to_f32(), f)
+  }
+}
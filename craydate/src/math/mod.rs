@@ -0,0 +1,130 @@
+//! Math functions and types that give the same results on the device and in the simulator.
+//!
+//! The basic operations on `f32` (`+`, `-`, `*`, `/` and comparisons) are correctly rounded by IEEE
+//! 754 on both the device's ARM processor and the simulator's host processor, so they give the same
+//! results everywhere, given the same inputs in the same order. Other functions are where results
+//! can drift apart:
+//!
+//! * Functions such as `sin()` or `pow()` are not required to be correctly rounded, and different
+//!   math libraries give different results in the last bits. The simulator may use the host's math
+//!   library, while the device uses another. The functions in this module always use the same
+//!   implementation, from the `libm` crate, so they give the same results in both places.
+//! * The compiler may fuse a multiply and an add into a single instruction with only one rounding
+//!   on one target and not another. Use `mul_add()` where a fused result is wanted, so that it is
+//!   fused everywhere.
+//! * Values given by the Playdate system, such as the crank angle or accelerometer, are inputs. For
+//!   a replay, record them rather than reading them again.
+//!
+//! A small difference in a single float can grow over many frames until a replay no longer matches.
+//! Where game state must be reproduced exactly, such as for replays or lockstep multiplayer, the
+//! `Fixed` type avoids floats entirely, and `StateChecksum` can find the frame where state first
+//! differs. The `Random` generator gives the same numbers from the same seed everywhere, so random
+//! content, such as a generated level, can be recreated from its seed.
+
+mod checksum;
+mod fixed;
+mod random;
+
+pub use checksum::StateChecksum;
+pub use fixed::Fixed;
+pub use random::Random;
+
+/// Returns the square root of `x`.
+pub fn sqrt(x: f32) -> f32 {
+  libm::sqrtf(x)
+}
+/// Returns the cube root of `x`.
+pub fn cbrt(x: f32) -> f32 {
+  libm::cbrtf(x)
+}
+/// Returns the length of the hypotenuse of a right triangle with sides `x` and `y`.
+pub fn hypot(x: f32, y: f32) -> f32 {
+  libm::hypotf(x, y)
+}
+/// Returns `x * y + z` computed with a single rounding.
+pub fn mul_add(x: f32, y: f32, z: f32) -> f32 {
+  libm::fmaf(x, y, z)
+}
+
+/// Returns the sine of `x`, in radians.
+pub fn sin(x: f32) -> f32 {
+  libm::sinf(x)
+}
+/// Returns the cosine of `x`, in radians.
+pub fn cos(x: f32) -> f32 {
+  libm::cosf(x)
+}
+/// Returns the sine and cosine of `x`, in radians.
+pub fn sin_cos(x: f32) -> (f32, f32) {
+  libm::sincosf(x)
+}
+/// Returns the tangent of `x`, in radians.
+pub fn tan(x: f32) -> f32 {
+  libm::tanf(x)
+}
+/// Returns the arcsine of `x`, in radians.
+pub fn asin(x: f32) -> f32 {
+  libm::asinf(x)
+}
+/// Returns the arccosine of `x`, in radians.
+pub fn acos(x: f32) -> f32 {
+  libm::acosf(x)
+}
+/// Returns the arctangent of `x`, in radians.
+pub fn atan(x: f32) -> f32 {
+  libm::atanf(x)
+}
+/// Returns the angle, in radians, of the point (`x`, `y`) from the positive x axis.
+pub fn atan2(y: f32, x: f32) -> f32 {
+  libm::atan2f(y, x)
+}
+
+/// Returns `e` raised to the power `x`.
+pub fn exp(x: f32) -> f32 {
+  libm::expf(x)
+}
+/// Returns 2 raised to the power `x`.
+pub fn exp2(x: f32) -> f32 {
+  libm::exp2f(x)
+}
+/// Returns the natural logarithm of `x`.
+pub fn ln(x: f32) -> f32 {
+  libm::logf(x)
+}
+/// Returns the base 2 logarithm of `x`.
+pub fn log2(x: f32) -> f32 {
+  libm::log2f(x)
+}
+/// Returns the base 10 logarithm of `x`.
+pub fn log10(x: f32) -> f32 {
+  libm::log10f(x)
+}
+/// Returns `x` raised to the power `y`.
+pub fn pow(x: f32, y: f32) -> f32 {
+  libm::powf(x, y)
+}
+
+/// Returns the largest whole number less than or equal to `x`.
+pub fn floor(x: f32) -> f32 {
+  libm::floorf(x)
+}
+/// Returns the smallest whole number greater than or equal to `x`.
+pub fn ceil(x: f32) -> f32 {
+  libm::ceilf(x)
+}
+/// Returns the nearest whole number to `x`, rounding half-way cases away from zero.
+pub fn round(x: f32) -> f32 {
+  libm::roundf(x)
+}
+/// Returns the nearest whole number to `x`, rounding half-way cases to the even number.
+pub fn round_ties_even(x: f32) -> f32 {
+  libm::rintf(x)
+}
+/// Returns the whole number part of `x`, by removing any fractional part.
+pub fn trunc(x: f32) -> f32 {
+  libm::truncf(x)
+}
+/// Returns the remainder of `x / y`, with the same sign as `x`.
+pub fn rem(x: f32, y: f32) -> f32 {
+  libm::fmodf(x, y)
+}
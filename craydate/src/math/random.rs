@@ -0,0 +1,70 @@
+/// A fast pseudo-random number generator, which gives the same sequence of numbers for the same
+/// seed on the device and in the simulator.
+///
+/// This is the xoshiro128** generator, which uses only 32-bit integer math. It is not suitable for
+/// cryptography, but is well suited to games, where the same seed can recreate a level or replay.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Random {
+  state: [u32; 4],
+}
+impl Random {
+  /// Constructs a generator whose sequence is determined by `seed`.
+  pub fn new(seed: u64) -> Self {
+    // Spread the seed over the state with SplitMix64, so that similar seeds give unrelated
+    // sequences, and the state is never all zero.
+    let mut s = seed;
+    let mut next = || {
+      s = s.wrapping_add(0x9e3779b97f4a7c15);
+      let mut z = s;
+      z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+      z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+      z ^ (z >> 31)
+    };
+    let (a, b) = (next(), next());
+    Random {
+      state: [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32],
+    }
+  }
+
+  /// Returns the next random `u32`, where every value is equally likely.
+  pub fn next_u32(&mut self) -> u32 {
+    let s = &mut self.state;
+    let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+    let t = s[1] << 9;
+    s[2] ^= s[0];
+    s[3] ^= s[1];
+    s[1] ^= s[2];
+    s[0] ^= s[3];
+    s[2] ^= t;
+    s[3] = s[3].rotate_left(11);
+    result
+  }
+  /// Returns a random `f32` from 0 up to (but not including) 1.
+  pub fn next_f32(&mut self) -> f32 {
+    // An f32 has 24 bits of precision, so use the top 24 bits.
+    (self.next_u32() >> 8) as f32 * (1.0 / (1 << 24) as f32)
+  }
+  /// Returns a random integer from `min` up to (but not including) `end`, where every value is
+  /// equally likely.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `end` is not greater than `min`.
+  pub fn range(&mut self, min: i32, end: i32) -> i32 {
+    assert!(end > min);
+    let span = (end as i64 - min as i64) as u64;
+    // Lemire's method: multiply into the range, and reject the few values that would make some
+    // results more likely than others.
+    let threshold = (1u64 << 32) % span;
+    loop {
+      let m = self.next_u32() as u64 * span;
+      if (m & 0xffff_ffff) >= threshold {
+        return (min as i64 + (m >> 32) as i64) as i32;
+      }
+    }
+  }
+  /// Returns true with a probability of `p`, where `p` is between 0 and 1.
+  pub fn chance(&mut self, p: f32) -> bool {
+    self.next_f32() < p
+  }
+}
@@ -1,3 +1,4 @@
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::ffi::c_void;
 use core::marker::PhantomData;
@@ -62,8 +63,8 @@ impl MenuItem {
     callback: MenuCallback<'a, T, F, Constructed>,
   ) -> MenuItem<Action> {
     let key = make_callback_key();
-    let (callbacks, cb) = callback.into_inner().unwrap();
-    let (func, reg) = callbacks.add_menu_item(key, cb);
+    let (callbacks, cb, meta) = callback.into_inner().unwrap();
+    let (func, reg) = callbacks.add_menu_item(key, cb, meta);
     let ptr = unsafe {
       Self::fns().addMenuItem.unwrap()(
         title.to_null_terminated_utf8().as_ptr(),
@@ -109,8 +110,8 @@ impl MenuItem {
     callback: MenuCallback<'a, T, F, Constructed>,
   ) -> MenuItem<Checkmark> {
     let key = make_callback_key();
-    let (callbacks, cb) = callback.into_inner().unwrap();
-    let (func, reg) = callbacks.add_menu_item(key, cb);
+    let (callbacks, cb, meta) = callback.into_inner().unwrap();
+    let (func, reg) = callbacks.add_menu_item(key, cb, meta);
     let ptr = unsafe {
       Self::fns().addCheckmarkMenuItem.unwrap()(
         title.to_null_terminated_utf8().as_ptr(),
@@ -157,8 +158,8 @@ impl MenuItem {
     callback: MenuCallback<'a, T, F, Constructed>,
   ) -> MenuItem<Options> {
     let key = make_callback_key();
-    let (callbacks, cb) = callback.into_inner().unwrap();
-    let (func, reg) = callbacks.add_menu_item(key, cb);
+    let (callbacks, cb, meta) = callback.into_inner().unwrap();
+    let (func, reg) = callbacks.add_menu_item(key, cb, meta);
     let options_null_terminated: Vec<_> =
       options.into_iter().map(|o| o.to_null_terminated_utf8()).collect();
     let options_pointers: Vec<_> = options_null_terminated.iter().map(|o| o.as_ptr()).collect();
@@ -177,6 +178,39 @@ impl MenuItem {
       _marker: PhantomData,
     }
   }
+
+  /// Construct a new options menu item with `option_count` options, where the text shown for each
+  /// option is made by the `format` function, and add it to the system menu as long as the MenuItem
+  /// stays alive.
+  ///
+  /// The `format` function is called with the index of each option, from 0 up to (but not
+  /// including) `option_count`. This is the same index as is returned from `value()`, so the game
+  /// can map it back to the value it represents.
+  ///
+  /// Otherwise, this is the same as `new_options()`.
+  ///
+  /// # Example
+  /// ```
+  /// let callbacks: Callbacks<i32> = Callbacks::new();
+  /// // Shows the volume as "0%" through "100%".
+  /// let volume = MenuItem::new_formatted_options(
+  ///   "Volume",
+  ///   11,
+  ///   |i| format!("{}%", i * 10),
+  ///   MenuCallback::with(&mut callbacks).call(|i: i32| {
+  ///     println("volume changed");
+  ///   }),
+  /// );
+  /// ```
+  pub fn new_formatted_options<'a, T, F: Fn(T) + 'static>(
+    title: &str,
+    option_count: i32,
+    format: impl FnMut(i32) -> String,
+    callback: MenuCallback<'a, T, F, Constructed>,
+  ) -> MenuItem<Options> {
+    let options: Vec<String> = (0..option_count).map(format).collect();
+    Self::new_options(title, options.iter().map(|o| o.as_str()), callback)
+  }
 }
 
 impl<T> MenuItem<T> {
@@ -188,6 +222,9 @@ impl<T> MenuItem<T> {
     unsafe { crate::null_terminated::parse_null_terminated_utf8(ptr).unwrap() }
   }
   /// Set the menu item's title.
+  ///
+  /// The title can be changed at any time while the menu item exists, such as to show some state of
+  /// the game in it, and the new title is shown the next time the menu is opened.
   pub fn set_title(&mut self, title: &str) {
     unsafe {
       Self::fns().setMenuItemTitle.unwrap()(self.cptr_mut(), title.to_null_terminated_utf8().as_ptr())
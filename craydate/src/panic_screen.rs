@@ -0,0 +1,305 @@
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::hash::Hasher;
+use core::panic::PanicInfo;
+
+use crate::capi_state::CApiState;
+use crate::ctypes::*;
+use crate::ctypes_enums::{LCD_COLUMNS, LCD_ROWS};
+use crate::graphics::{Font, Graphics, QrCode, QrErrorCorrection, SystemFont};
+use crate::math::StateChecksum;
+
+/// The longest panic message that is shown, in bytes. Longer messages are cut off.
+const MAX_MESSAGE_BYTES: usize = 256;
+/// The longest report that is put in the QR code, in bytes.
+const MAX_REPORT_BYTES: usize = 512;
+/// The space left around the edges of the screen and between the text and the QR code.
+const MARGIN: i32 = 8;
+/// The amount of memory set aside for drawing the panic screen, in bytes, which is more than
+/// encoding the largest report in a QR code needs.
+const RESERVED_BYTES: usize = 32 * 1024;
+
+static mut PANIC_SCREEN: Option<PanicScreen> = None;
+// The font the panic screen is drawn with, which is found when the panic screen is set so that the
+// panic does not need to look it up.
+static mut PANIC_FONT: Option<&'static Font> = None;
+// Memory set aside when the panic screen is set, which is freed when a panic starts so that drawing
+// the panic screen can allocate, even if the game has run out of memory.
+static mut PANIC_RESERVE: Option<Vec<u8>> = None;
+// Set once a panic has started drawing the panic screen, so that a panic while drawing it aborts
+// instead of trying again.
+static mut SHOWING_PANIC_SCREEN: bool = false;
+
+/// The text of a screen shown to players when the game panics in a release build, so that the
+/// error can be reported.
+///
+/// The screen shows the panic message and a QR code holding a report of the error: the message,
+/// where in the code the panic happened, the game's version, and a crash id. The crash id is a hash
+/// of the message and location, so that reports of the same crash have the same id. Players can
+/// photograph the QR code, or scan it with their phone, and send it along with their bug report.
+///
+/// The screen is turned on with `set_panic_screen()`. It is only shown on the device, and not in
+/// debug builds, where the panic is logged and the game stops as usual.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PanicScreen {
+  /// The heading at the top of the screen.
+  pub title: &'static str,
+  /// Text shown below the panic message, asking the player to report the error.
+  pub instructions: &'static str,
+  /// The game's version, which is included in the report so that it can be matched to the code
+  /// that crashed.
+  pub version: &'static str,
+}
+impl PanicScreen {
+  /// Constructs a panic screen with a default title and instructions, for a game at `version`.
+  pub const fn new(version: &'static str) -> Self {
+    PanicScreen {
+      title: "Something went wrong",
+      instructions: "Photograph this screen to report the problem, then restart the Playdate.",
+      version,
+    }
+  }
+}
+
+/// Sets the screen to show when the game panics in a release build, or turns it off with `None`.
+///
+/// There is no panic screen by default. When one is set, a panic in a release build on the device
+/// draws it to the display and then stops the game in place, so that the screen stays up. Control
+/// never returns to the system, so the system menu can not be opened to exit the game, and the
+/// player has to restart the device.
+///
+/// The screen is not shown in the simulator, where stopping the game in place would hang the
+/// simulator, so the game is stopped as it would be without a panic screen.
+///
+/// Setting a panic screen sets aside a small amount of memory, which is freed when the game panics
+/// so that the screen can be drawn even if the game has run out of memory. The screen may not be
+/// shown if the panic happens before the game's main function is called, or if the panic happens
+/// while drawing the screen. In those cases the game stops as it would without a panic screen.
+pub fn set_panic_screen(screen: Option<PanicScreen>) {
+  unsafe {
+    PANIC_SCREEN = screen;
+    match screen {
+      Some(_) if is_shown() => {
+        if CApiState::try_get().is_some() {
+          PANIC_FONT = Font::system(SystemFont::DEFAULT).ok();
+        }
+        PANIC_RESERVE = Some(Vec::with_capacity(RESERVED_BYTES));
+      }
+      _ => PANIC_RESERVE = None,
+    }
+  }
+}
+
+/// Draws the panic screen for `info` and then never returns, if a panic screen is set and this is a
+/// release build on the device. Otherwise returns without doing anything.
+pub(crate) fn show_panic_screen(info: &PanicInfo) {
+  if !is_shown() {
+    return;
+  }
+  let screen = match unsafe { PANIC_SCREEN } {
+    Some(screen) => screen,
+    None => return,
+  };
+  if CApiState::try_get().is_none() || unsafe { SHOWING_PANIC_SCREEN } {
+    return;
+  }
+  unsafe {
+    SHOWING_PANIC_SCREEN = true;
+    PANIC_RESERVE = None;
+  }
+
+  let mut message = FixedString::<MAX_MESSAGE_BYTES>::new();
+  let _ = write!(message, "{}", info.message());
+  let mut checksum = StateChecksum::new();
+  checksum.write(message.as_str().as_bytes());
+  let mut location = FixedString::<MAX_MESSAGE_BYTES>::new();
+  if let Some(loc) = info.location() {
+    let _ = write!(location, "{}:{}:{}", loc.file(), loc.line(), loc.column());
+    checksum.write(loc.file().as_bytes());
+    checksum.write_u32(loc.line());
+    checksum.write_u32(loc.column());
+  }
+  let crash_id = checksum.finish();
+
+  let mut report = FixedString::<MAX_REPORT_BYTES>::new();
+  let _ = write!(
+    report,
+    "{}\nat {}\nversion {}\ncrash id {:016x}",
+    message.as_str(),
+    location.as_str(),
+    screen.version,
+    crash_id
+  );
+
+  draw(&screen, message.as_str(), report.as_str(), crash_id);
+  loop {
+    core::hint::spin_loop();
+  }
+}
+
+/// Whether the panic screen is shown in this build, which is only a release build on the device.
+fn is_shown() -> bool {
+  !cfg!(debug_assertions) && cfg!(all(target_arch = "arm", target_os = "none"))
+}
+
+fn draw(screen: &PanicScreen, message: &str, report: &str, crash_id: u64) {
+  let mut graphics = Graphics::new();
+  // Undo anything the game has changed about drawing and the display, which could hide or distort
+  // the screen. Pushing a null context targets the display's frame buffer, even if the game was
+  // drawing into a bitmap.
+  unsafe {
+    let cgraphics = Graphics::fns();
+    cgraphics.pushContext.unwrap()(core::ptr::null_mut());
+    cgraphics.setStencil.unwrap()(core::ptr::null_mut());
+    cgraphics.setDrawMode.unwrap()(BitmapDrawMode::kDrawModeCopy);
+    cgraphics.setDrawOffset.unwrap()(0, 0);
+    cgraphics.clearClipRect.unwrap()();
+
+    let cdisplay = CApiState::get().cdisplay;
+    cdisplay.setScale.unwrap()(1);
+    cdisplay.setInverted.unwrap()(0);
+    cdisplay.setMosaic.unwrap()(0, 0);
+    cdisplay.setFlipped.unwrap()(0, 0);
+    cdisplay.setOffset.unwrap()(0, 0);
+  }
+  graphics.clear(SolidColor::kColorWhite);
+
+  // The QR code goes on the right, as large as fits in the height of the screen. The QR code's
+  // quiet zone leaves a margin between it and the text.
+  let qr = QrCode::encode_text(report, QrErrorCorrection::Low).ok();
+  let scale = qr.as_ref().map_or(0, |qr| {
+    ((LCD_ROWS as i32 - MARGIN) / qr.drawn_size(1)).max(1)
+  });
+  let qr_size = qr.as_ref().map_or(0, |qr| qr.drawn_size(scale));
+  let qr_x = LCD_COLUMNS as i32 - qr_size;
+  let text_right = if qr.is_some() {
+    qr_x
+  } else {
+    LCD_COLUMNS as i32 - MARGIN
+  };
+
+  let font = unsafe { PANIC_FONT };
+  if let Some(font) = font {
+    // Dropping the ActiveFont sets the system's default font, which is this same font.
+    let _ = graphics.set_font(font);
+  }
+  let line_height = font.map_or(18, |f| f.font_height() as i32 + 2);
+  // The crash id and version go at the bottom, below the rest of the text, so they are shown
+  // however long the message is.
+  let footer_y = LCD_ROWS as i32 - MARGIN - line_height * 2;
+  let mut text = TextColumn {
+    graphics: &mut graphics,
+    font,
+    width: text_right - MARGIN * 2,
+    line_height,
+    y: MARGIN,
+    bottom: footer_y,
+  };
+  text.draw_wrapped(screen.title);
+  text.y += line_height / 2;
+  text.draw_wrapped(message);
+  text.y += line_height / 2;
+  text.draw_wrapped(screen.instructions);
+
+  let mut footer = FixedString::<64>::new();
+  let _ = write!(footer, "crash id {:016x}", crash_id);
+  text.y = footer_y;
+  text.bottom = LCD_ROWS as i32;
+  text.draw_wrapped(footer.as_str());
+  text.draw_wrapped(screen.version);
+
+  // The QR code is drawn last, so that text which is too wide for its column does not cover it.
+  if let Some(qr) = qr {
+    qr.draw(&mut graphics, qr_x, (LCD_ROWS as i32 - qr_size) / 2, scale);
+  }
+  graphics.display();
+}
+
+/// Draws lines of text down a column on the left of the screen.
+struct TextColumn<'a> {
+  graphics: &'a mut Graphics,
+  font: Option<&'static Font>,
+  width: i32,
+  line_height: i32,
+  y: i32,
+  // Lines which would go below this are not drawn.
+  bottom: i32,
+}
+impl TextColumn<'_> {
+  /// Draws `text` starting on a new line, breaking it between words into as many lines as are
+  /// needed to fit in the column. Lines that do not fit above the bottom are dropped.
+  fn draw_wrapped(&mut self, text: &str) {
+    for paragraph in text.lines() {
+      // A line that fills the buffer is far wider than the screen, so it is broken before then, and
+      // only a single very long word could be cut off.
+      let mut line = FixedString::<MAX_MESSAGE_BYTES>::new();
+      for word in paragraph.split(' ') {
+        let len = line.len;
+        if len > 0 {
+          let _ = line.write_str(" ");
+        }
+        let _ = line.write_str(word);
+        if len > 0 && self.measure(line.as_str()) > self.width {
+          line.truncate(len);
+          self.draw_line(line.as_str());
+          line.truncate(0);
+          let _ = line.write_str(word);
+        }
+      }
+      self.draw_line(line.as_str());
+    }
+  }
+
+  fn draw_line(&mut self, line: &str) {
+    if self.y + self.line_height <= self.bottom {
+      self.graphics.draw_text(line, MARGIN, self.y);
+    }
+    self.y += self.line_height;
+  }
+
+  fn measure(&self, text: &str) -> i32 {
+    match self.font {
+      Some(font) => font.measure_text_width(text, 0),
+      // Without the font, guess at the width of an average character.
+      None => text.chars().count() as i32 * 8,
+    }
+  }
+}
+
+/// A string stored in a fixed-size buffer, so that it can be written without allocating. Anything
+/// written past the end of the buffer is dropped.
+struct FixedString<const N: usize> {
+  bytes: [u8; N],
+  len: usize,
+}
+impl<const N: usize> FixedString<N> {
+  fn new() -> Self {
+    FixedString {
+      bytes: [0; N],
+      len: 0,
+    }
+  }
+  fn as_str(&self) -> &str {
+    // Only whole chars are ever written.
+    unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.len]) }
+  }
+  /// Shortens the string to `len` bytes, which must be a length that the string had before.
+  fn truncate(&mut self, len: usize) {
+    self.len = len;
+  }
+}
+impl<const N: usize> Write for FixedString<N> {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    let mut end = s.len().min(N - self.len);
+    while !s.is_char_boundary(end) {
+      end -= 1;
+    }
+    self.bytes[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+    self.len += end;
+    if end < s.len() {
+      Err(core::fmt::Error)
+    } else {
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+
+use crate::grid::BitGrid;
+use crate::math::Random;
+
+/// The sizes used to generate a `BspDungeon`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BspOptions {
+  /// The smallest width or height of an area that a room is placed in. Areas are split in two until
+  /// they would become smaller than this.
+  pub min_leaf_size: i32,
+  /// The smallest width or height of a room.
+  pub min_room_size: i32,
+  /// The number of wall cells kept between each room and the edge of its area, at least.
+  pub room_margin: i32,
+  /// The width of the corridors between rooms.
+  pub corridor_width: i32,
+}
+impl Default for BspOptions {
+  fn default() -> Self {
+    BspOptions {
+      min_leaf_size: 10,
+      min_room_size: 4,
+      room_margin: 1,
+      corridor_width: 1,
+    }
+  }
+}
+
+/// A dungeon of rectangular rooms joined by corridors, generated by binary space partitioning.
+///
+/// The dungeon's area is split in two, along its longer side at a random position, and each half is
+/// split again until the areas are too small to split. A room of random size is placed in each of
+/// those areas. Then, for each split, a room on one side is joined to a room on the other side with
+/// an L-shaped corridor, so that every room can be reached.
+#[derive(Debug, Clone)]
+pub struct BspDungeon {
+  floor: BitGrid,
+  rooms: Vec<euclid::default::Rect<i32>>,
+}
+impl BspDungeon {
+  /// Generates a dungeon of `width` by `height` cells.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the `options.min_leaf_size` is too small to fit a room of `options.min_room_size`
+  /// with its margins.
+  pub fn generate(width: i32, height: i32, options: &BspOptions, random: &mut Random) -> Self {
+    assert!(options.min_room_size > 0);
+    assert!(options.min_leaf_size >= options.min_room_size + options.room_margin * 2);
+    let mut dungeon = BspDungeon {
+      floor: BitGrid::new(width, height),
+      rooms: Vec::new(),
+    };
+    let area = euclid::rect(0, 0, width, height);
+    if width >= options.min_leaf_size && height >= options.min_leaf_size {
+      dungeon.split(area, options, random);
+    }
+    dungeon
+  }
+
+  /// The cells of the dungeon, where set cells are floor, in the rooms and corridors, and unset
+  /// cells are walls.
+  pub fn floor(&self) -> &BitGrid {
+    &self.floor
+  }
+  /// Takes the floor cells out of the dungeon. See `floor()`.
+  pub fn into_floor(self) -> BitGrid {
+    self.floor
+  }
+  /// The rectangles of the rooms in the dungeon, which do not overlap.
+  ///
+  /// Rooms next to each other in the list are close together in the dungeon. The first and last
+  /// rooms are in opposite halves of the dungeon, which makes them a good choice for the start and
+  /// the exit.
+  pub fn rooms(&self) -> &[euclid::default::Rect<i32>] {
+    &self.rooms
+  }
+
+  /// Splits the `area` until it is too small, placing rooms in each part and joining them. Returns
+  /// the range of `rooms` that were placed in the area.
+  fn split(
+    &mut self,
+    area: euclid::default::Rect<i32>,
+    options: &BspOptions,
+    random: &mut Random,
+  ) -> core::ops::Range<usize> {
+    let min = options.min_leaf_size;
+    let (w, h) = (area.width(), area.height());
+    let vertical = match (w >= min * 2, h >= min * 2) {
+      (false, false) => return self.place_room(area, options, random),
+      (true, false) => true,
+      (false, true) => false,
+      // Prefer to split the longer side, to avoid long thin areas.
+      (true, true) if w * 4 > h * 5 => true,
+      (true, true) if h * 4 > w * 5 => false,
+      (true, true) => random.chance(0.5),
+    };
+
+    let (first, second) = if vertical {
+      let at = random.range(min, w - min + 1);
+      (
+        euclid::rect(area.min_x(), area.min_y(), at, h),
+        euclid::rect(area.min_x() + at, area.min_y(), w - at, h),
+      )
+    } else {
+      let at = random.range(min, h - min + 1);
+      (
+        euclid::rect(area.min_x(), area.min_y(), w, at),
+        euclid::rect(area.min_x(), area.min_y() + at, w, h - at),
+      )
+    };
+    let first = self.split(first, options, random);
+    let second = self.split(second, options, random);
+
+    let a = self.rooms[random.range(first.start as i32, first.end as i32) as usize];
+    let b = self.rooms[random.range(second.start as i32, second.end as i32) as usize];
+    carve_corridor_between(&mut self.floor, a, b, options.corridor_width, random);
+    first.start..second.end
+  }
+
+  fn place_room(
+    &mut self,
+    area: euclid::default::Rect<i32>,
+    options: &BspOptions,
+    random: &mut Random,
+  ) -> core::ops::Range<usize> {
+    let inner = area.inflate(-options.room_margin, -options.room_margin);
+    let w = random.range(options.min_room_size, inner.width() + 1);
+    let h = random.range(options.min_room_size, inner.height() + 1);
+    let x = inner.min_x() + random.range(0, inner.width() - w + 1);
+    let y = inner.min_y() + random.range(0, inner.height() - h + 1);
+    let room = euclid::rect(x, y, w, h);
+    self.floor.fill_rect(room, true);
+    self.rooms.push(room);
+    self.rooms.len() - 1..self.rooms.len()
+  }
+}
+
+fn carve_corridor_between(
+  floor: &mut BitGrid,
+  a: euclid::default::Rect<i32>,
+  b: euclid::default::Rect<i32>,
+  width: i32,
+  random: &mut Random,
+) {
+  let center = |r: euclid::default::Rect<i32>| {
+    euclid::point2(r.min_x() + r.width() / 2, r.min_y() + r.height() / 2)
+  };
+  super::carve_corridor(floor, center(a), center(b), width, random)
+}
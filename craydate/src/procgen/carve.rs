@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+
+use crate::grid::BitGrid;
+use crate::math::Random;
+
+/// Carves an L-shaped corridor of floor, `width` cells wide, from `from` to `to` in `floor`.
+///
+/// The corridor goes horizontally then vertically, or vertically then horizontally, chosen by
+/// `random`.
+pub fn carve_corridor(
+  floor: &mut BitGrid,
+  from: euclid::default::Point2D<i32>,
+  to: euclid::default::Point2D<i32>,
+  width: i32,
+  random: &mut Random,
+) {
+  let corner = if random.chance(0.5) {
+    euclid::point2(to.x, from.y)
+  } else {
+    euclid::point2(from.x, to.y)
+  };
+  carve_straight(floor, from, corner, width);
+  carve_straight(floor, corner, to, width);
+}
+
+/// Carves a straight horizontal or vertical line of floor from `a` to `b`, `width` cells wide.
+fn carve_straight(
+  floor: &mut BitGrid,
+  a: euclid::default::Point2D<i32>,
+  b: euclid::default::Point2D<i32>,
+  width: i32,
+) {
+  // The corridor is centered on the line, with any extra cell on the bottom or right.
+  let offset = (width - 1) / 2;
+  let min = a.min(b) - euclid::vec2(offset, offset);
+  let max = a.max(b) - euclid::vec2(offset, offset) + euclid::vec2(width, width);
+  floor.fill_rect(euclid::default::Box2D::new(min, max).to_rect(), true);
+}
+
+/// Returns the largest region of set cells in `floor`, where cells are connected through their
+/// sides, with every other cell unset.
+///
+/// Generators such as `CellularCaves` can leave pockets of floor that can not be reached from the
+/// rest, and this removes them.
+pub fn largest_region(floor: &BitGrid) -> BitGrid {
+  let (width, height) = (floor.width(), floor.height());
+  let mut seen = BitGrid::new(width, height);
+  let mut best = BitGrid::new(width, height);
+  let mut best_size = 0;
+  let mut region = Vec::new();
+  let mut stack = Vec::new();
+  for y in 0..height {
+    for x in 0..width {
+      if !floor.get(x, y) || seen.get(x, y) {
+        continue;
+      }
+      region.clear();
+      seen.set(x, y, true);
+      stack.push((x, y));
+      while let Some((cx, cy)) = stack.pop() {
+        region.push((cx, cy));
+        for (nx, ny) in [(cx - 1, cy), (cx + 1, cy), (cx, cy - 1), (cx, cy + 1)] {
+          if floor.get(nx, ny) && !seen.get(nx, ny) {
+            seen.set(nx, ny, true);
+            stack.push((nx, ny));
+          }
+        }
+      }
+      if region.len() > best_size {
+        best_size = region.len();
+        best.fill(false);
+        for &(rx, ry) in &region {
+          best.set(rx, ry, true);
+        }
+      }
+    }
+  }
+  best
+}
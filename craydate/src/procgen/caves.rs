@@ -0,0 +1,88 @@
+use crate::grid::BitGrid;
+use crate::math::Random;
+
+/// Generates natural looking caves with a cellular automaton.
+///
+/// The grid starts as random noise, where each cell is a wall with a chance of `wall_chance`. Then
+/// each step smooths it: a wall stays a wall if at least `survival_limit` of its 8 neighbours are
+/// walls, and a floor becomes a wall if at least `birth_limit` of them are. Cells outside the grid
+/// count as walls, and the cells along the edges are always walls, so the caves are closed in.
+///
+/// The defaults give open, connected caves. A higher `wall_chance` gives narrower passages, and more
+/// `steps` give smoother walls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellularCaves {
+  /// The chance, between 0 and 1, that each cell starts as a wall.
+  pub wall_chance: f32,
+  /// The number of smoothing steps to run.
+  pub steps: u32,
+  /// The number of wall neighbours needed for a floor to become a wall.
+  pub birth_limit: u32,
+  /// The number of wall neighbours needed for a wall to stay a wall.
+  pub survival_limit: u32,
+  /// Whether to fill in every area of floor except the largest, so that all the floor is connected.
+  pub keep_largest_region: bool,
+}
+impl Default for CellularCaves {
+  fn default() -> Self {
+    CellularCaves {
+      wall_chance: 0.45,
+      steps: 4,
+      birth_limit: 5,
+      survival_limit: 4,
+      keep_largest_region: true,
+    }
+  }
+}
+impl CellularCaves {
+  /// Generates caves of `width` by `height` cells, where set cells are floor.
+  pub fn generate(&self, width: i32, height: i32, random: &mut Random) -> BitGrid {
+    let mut walls = BitGrid::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        walls.set(x, y, random.chance(self.wall_chance));
+      }
+    }
+
+    let mut next = BitGrid::new(width, height);
+    for _ in 0..self.steps {
+      for y in 0..height {
+        for x in 0..width {
+          let neighbours = wall_neighbours(&walls, x, y);
+          let limit = if walls.get(x, y) {
+            self.survival_limit
+          } else {
+            self.birth_limit
+          };
+          next.set(x, y, neighbours >= limit);
+        }
+      }
+      core::mem::swap(&mut walls, &mut next);
+    }
+    walls.fill_rect(euclid::rect(0, 0, width, 1), true);
+    walls.fill_rect(euclid::rect(0, height - 1, width, 1), true);
+    walls.fill_rect(euclid::rect(0, 0, 1, height), true);
+    walls.fill_rect(euclid::rect(width - 1, 0, 1, height), true);
+
+    let mut floor = BitGrid::new(width, height);
+    floor.fill(true);
+    floor.subtract(&walls);
+    if self.keep_largest_region {
+      floor = super::largest_region(&floor);
+    }
+    floor
+  }
+}
+
+/// Counts the walls around the cell at (`x`, `y`), where cells outside the grid are walls.
+fn wall_neighbours(walls: &BitGrid, x: i32, y: i32) -> u32 {
+  let mut count = 0;
+  for ny in y - 1..=y + 1 {
+    for nx in x - 1..=x + 1 {
+      if (nx, ny) != (x, y) && (!walls.contains(nx, ny) || walls.get(nx, ny)) {
+        count += 1;
+      }
+    }
+  }
+  count
+}
@@ -0,0 +1,14 @@
+//! Building blocks for generating random levels, such as the dungeons and caves of a roguelike.
+//!
+//! The generators produce a `BitGrid` where set cells are floor and unset cells are wall, which can
+//! be used directly for collision, given to a `Fog`, or turned into tiles with
+//! `ByteGrid::from_bit_grid()`. They take their randomness from a `Random`, so a level can be
+//! recreated exactly from its seed, on the device or in the simulator.
+
+mod bsp;
+mod caves;
+mod carve;
+
+pub use bsp::{BspDungeon, BspOptions};
+pub use carve::{carve_corridor, largest_region};
+pub use caves::CellularCaves;
@@ -0,0 +1,178 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+static mut SCOPE_ID: usize = 0;
+/// Makes a unique id for each `ResourceScope`, and each time one is cleared, so that a
+/// `ScopedHandle` can only refer to the resource it was made for.
+fn make_scope_id() -> usize {
+  unsafe {
+    SCOPE_ID += 1;
+    SCOPE_ID
+  }
+}
+
+/// Any type can be held in a `ResourceScope`. The trait is only used to hold the resources as trait
+/// objects, so that they can be dropped without knowing their type.
+trait ScopedResource {}
+impl<T> ScopedResource for T {}
+
+/// Runs a closure when dropped, for `ResourceScope::on_exit()`.
+struct OnExit<F: FnOnce()>(Option<F>);
+impl<F: FnOnce()> Drop for OnExit<F> {
+  fn drop(&mut self) {
+    if let Some(f) = self.0.take() {
+      f()
+    }
+  }
+}
+
+/// A handle to a resource held in a `ResourceScope`, which is returned from `ResourceScope::add()`.
+///
+/// The handle can be used to access the resource through the scope that holds it, until the
+/// resource is taken back out of the scope, or the scope is cleared.
+pub struct ScopedHandle<R> {
+  scope: usize,
+  index: usize,
+  // Invariant in `R`, so that a handle can not be used to write a resource with a shorter lifetime
+  // through `get_mut()`.
+  _marker: PhantomData<fn(R) -> R>,
+}
+impl<R> Clone for ScopedHandle<R> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+impl<R> Copy for ScopedHandle<R> {}
+impl<R> core::fmt::Debug for ScopedHandle<R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("ScopedHandle").field("scope", &self.scope).field("index", &self.index).finish()
+  }
+}
+
+/// Owns the resources of a part of the game, such as a scene, and drops them all together when it
+/// ends.
+///
+/// Bitmaps, fonts, sounds, players, menu items, and anything else can be added to the scope, which
+/// takes ownership of them. Types which register a callback, such as a `MenuItem` or a
+/// `SamplePlayer` with a completion callback, unregister it when they are dropped, so adding them to
+/// the scope also unregisters their callbacks when the scope ends. Other cleanup can be added with
+/// `on_exit()`.
+///
+/// When the scope is cleared with `clear()`, or dropped, its resources are dropped in the reverse
+/// order they were added. This keeps the scene's resources from leaking into the next scene, without
+/// having to keep track of each of them and the order they must be dropped in.
+///
+/// Resources may borrow from anything that lives for `'a`. For example, a `SamplePlayer` that plays
+/// an `AudioSample` can be added to a scene's scope when the sample is held in a scope for the whole
+/// game, which outlives the scene's scope.
+pub struct ResourceScope<'a> {
+  id: usize,
+  // The resources in the order they were added. A resource taken out of the scope leaves None in
+  // its place, so that the index of every other resource stays the same.
+  resources: Vec<Option<Box<dyn ScopedResource + 'a>>>,
+}
+impl<'a> ResourceScope<'a> {
+  /// Constructs an empty scope.
+  pub fn new() -> Self {
+    ResourceScope {
+      id: make_scope_id(),
+      resources: Vec::new(),
+    }
+  }
+
+  /// Moves the `resource` into the scope, where it will be dropped when the scope is cleared or
+  /// dropped. Returns a handle to access the resource through the scope.
+  pub fn add<R: 'a>(&mut self, resource: R) -> ScopedHandle<R> {
+    let index = self.resources.len();
+    self.resources.push(Some(Box::new(resource)));
+    ScopedHandle {
+      scope: self.id,
+      index,
+      _marker: PhantomData,
+    }
+  }
+
+  /// Registers a closure to be run when the scope is cleared or dropped.
+  ///
+  /// It is run in the reverse order of everything added to the scope, so it runs before any
+  /// resources that were added earlier are dropped.
+  pub fn on_exit<F: FnOnce() + 'a>(&mut self, f: F) {
+    self.add(OnExit(Some(f)));
+  }
+
+  /// Returns the resource for `handle`, or None if it has been taken out of the scope, or was not
+  /// added to this scope.
+  pub fn get<R: 'a>(&self, handle: ScopedHandle<R>) -> Option<&R> {
+    let index = self.index_of(handle)?;
+    let resource = self.resources[index].as_deref()?;
+    // SAFETY: The scope id and index identify the resource that the handle was made for, which has
+    // type `R`.
+    Some(unsafe { &*(resource as *const dyn ScopedResource as *const R) })
+  }
+  /// Returns the resource for `handle`, or None if it has been taken out of the scope, or was not
+  /// added to this scope.
+  pub fn get_mut<R: 'a>(&mut self, handle: ScopedHandle<R>) -> Option<&mut R> {
+    let index = self.index_of(handle)?;
+    let resource = self.resources[index].as_deref_mut()?;
+    // SAFETY: The scope id and index identify the resource that the handle was made for, which has
+    // type `R`.
+    Some(unsafe { &mut *(resource as *mut dyn ScopedResource as *mut R) })
+  }
+  /// Moves the resource for `handle` out of the scope, so it is no longer dropped with the scope.
+  ///
+  /// Returns None if it has already been taken out of the scope, or was not added to this scope.
+  pub fn take<R: 'a>(&mut self, handle: ScopedHandle<R>) -> Option<R> {
+    let index = self.index_of(handle)?;
+    let resource = self.resources[index].take()?;
+    // SAFETY: The scope id and index identify the resource that the handle was made for, which has
+    // type `R`, and was allocated as a `Box<R>`.
+    Some(*unsafe { Box::from_raw(Box::into_raw(resource) as *mut R) })
+  }
+
+  /// The number of resources held in the scope, including closures from `on_exit()`.
+  pub fn len(&self) -> usize {
+    self.resources.iter().filter(|r| r.is_some()).count()
+  }
+  /// Whether the scope holds no resources.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Drops every resource held in the scope, in the reverse order they were added, and runs the
+  /// closures from `on_exit()`. The scope can then be used again, such as when a scene is entered
+  /// again.
+  ///
+  /// Handles to the dropped resources will no longer find anything in the scope.
+  pub fn clear(&mut self) {
+    // Resources are dropped one at a time, in reverse order, as dropping a Vec would drop them in
+    // the order they were added.
+    while let Some(resource) = self.resources.pop() {
+      drop(resource);
+    }
+    self.id = make_scope_id();
+  }
+
+  fn index_of<R>(&self, handle: ScopedHandle<R>) -> Option<usize> {
+    if handle.scope == self.id && handle.index < self.resources.len() {
+      Some(handle.index)
+    } else {
+      None
+    }
+  }
+}
+impl Default for ResourceScope<'_> {
+  fn default() -> Self {
+    ResourceScope::new()
+  }
+}
+impl Drop for ResourceScope<'_> {
+  fn drop(&mut self) {
+    self.clear()
+  }
+}
+impl core::fmt::Debug for ResourceScope<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("ResourceScope").field("len", &self.len()).finish()
+  }
+}
@@ -0,0 +1,74 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::capi_state::CApiState;
+use crate::time::TimeDelta;
+
+/// The time budget given to a hook registered with `on_shutdown()`.
+pub const DEFAULT_SHUTDOWN_HOOK_BUDGET: TimeDelta = TimeDelta::from_milliseconds(100);
+
+/// Why the shutdown hooks registered with `on_shutdown()` are being run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ShutdownReason {
+  /// The player chose to exit the game, and it will be terminated once the hooks return.
+  Terminate,
+  /// The device is being locked. The game may never be resumed if the battery runs out while
+  /// locked, so this is the last chance to save its state.
+  Lock,
+}
+
+pub(crate) struct ShutdownHook {
+  budget: TimeDelta,
+  hook: Box<dyn FnMut(ShutdownReason)>,
+}
+
+/// Registers `hook` to be run when the game is about to be terminated, or the device is about to be
+/// locked, with a time budget of `DEFAULT_SHUTDOWN_HOOK_BUDGET`.
+///
+/// This is the place to flush save files and fade out audio, which must happen no matter where the
+/// game's code is when the player exits. See `on_shutdown_with_budget()` for more.
+pub fn on_shutdown<F: FnMut(ShutdownReason) + 'static>(hook: F) {
+  on_shutdown_with_budget(DEFAULT_SHUTDOWN_HOOK_BUDGET, hook)
+}
+
+/// Registers `hook` to be run when the game is about to be terminated, or the device is about to be
+/// locked, which is expected to take no longer than `budget`.
+///
+/// Hooks are run in the order they were registered, after the `SystemEvent::WillTerminate` or
+/// `SystemEvent::WillLock` event has been handled by the game, so that any state changed by the game
+/// in response to the event is seen by the hooks. Every hook is run, even if earlier hooks go over
+/// their budget, as skipping a hook could lose the player's progress. Instead, a hook which takes
+/// longer than its budget is reported in the console, so that it can be found and fixed during
+/// development.
+pub fn on_shutdown_with_budget<F: FnMut(ShutdownReason) + 'static>(budget: TimeDelta, hook: F) {
+  CApiState::get().shutdown_hooks.borrow_mut().push(ShutdownHook {
+    budget,
+    hook: Box::new(hook),
+  })
+}
+
+/// Runs all the registered shutdown hooks, in the order they were registered.
+pub(crate) fn run_shutdown_hooks(reason: ShutdownReason) {
+  let capi = CApiState::get();
+  // The hooks are moved out while running, so that a hook can register another hook. Any hooks
+  // registered while running are kept after the existing ones, but are not run until next time.
+  let mut hooks: Vec<ShutdownHook> = core::mem::take(&mut *capi.shutdown_hooks.borrow_mut());
+
+  let now = || unsafe { capi.csystem.getCurrentTimeMilliseconds.unwrap()() };
+  for (index, hook) in hooks.iter_mut().enumerate() {
+    let start = now();
+    (hook.hook)(reason);
+    let elapsed = TimeDelta::from_milliseconds(now().wrapping_sub(start) as i32);
+    if elapsed > hook.budget {
+      crate::log::log(format!(
+        "Shutdown hook {} took {} for {:?}, which is over its budget of {}.",
+        index, elapsed, reason, hook.budget
+      ));
+    }
+  }
+
+  let mut registered = capi.shutdown_hooks.borrow_mut();
+  hooks.append(&mut registered);
+  *registered = hooks;
+}
@@ -0,0 +1,243 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use super::effects::sound_effect::SoundEffect;
+use super::sound_channel::SoundChannel;
+use super::SAMPLE_FRAMES_PER_SEC;
+use crate::ctypes::*;
+use crate::error::Error;
+use crate::files::{FileMode, OpenFile};
+
+/// The number of sample frames that can be waiting to be written to the file, which is 2 seconds of
+/// audio.
+const RING_FRAMES: usize = SAMPLE_FRAMES_PER_SEC as usize * 2;
+/// The size of the WAV header written at the start of the file.
+const WAV_HEADER_BYTES: usize = 44;
+
+/// Records the audio played through a `SoundChannel` to a 16-bit stereo WAV file, while the game
+/// runs in the simulator.
+///
+/// The recorder is added to the end of the channel's effects, where it sees the channel's mixed
+/// audio, and passes it through unchanged. Recording the default channel, from
+/// `Sound::default_channel_mut()`, captures the game's sound for a trailer, or to debug what was
+/// played. The file is written to the game's Data folder, which in the simulator is a folder on the
+/// computer's disk.
+///
+/// The audio is captured on the audio thread, and written to the file by `update()`, which should be
+/// called each frame. Call `finish()` to complete the file. If `update()` falls more than 2 seconds
+/// behind, audio is dropped, and reported by `dropped_frames()`.
+///
+/// This is only available in the simulator, and is compiled out for the device.
+pub struct AudioRecorder {
+  effect: ManuallyDrop<SoundEffect>,
+  ptr: NonNull<CSoundEffect>,
+  ring: Box<SampleRing>,
+  file: Option<OpenFile>,
+  frames_written: u32,
+}
+impl AudioRecorder {
+  /// Starts recording the audio played through `channel` to a WAV file at `path` in the game's Data
+  /// folder. Any existing file at `path` is replaced.
+  pub fn start(channel: &mut SoundChannel, path: &str) -> Result<Self, Error> {
+    let mut file = OpenFile::open(path, FileMode::Write)?;
+    // The sizes in the header are filled in by `finish()`.
+    file.write(&wav_header(0))?;
+
+    let ring = Box::new(SampleRing::new());
+    let ptr = unsafe {
+      SoundEffect::fns().newEffect.unwrap()(
+        Some(c_tap_function),
+        &*ring as *const SampleRing as *mut c_void,
+      )
+    };
+    let mut recorder = AudioRecorder {
+      effect: ManuallyDrop::new(SoundEffect::from_ptr(ptr)),
+      ptr: NonNull::new(ptr).unwrap(),
+      ring,
+      file: Some(file),
+      frames_written: 0,
+    };
+    channel.add_sound_effect(&mut recorder)?;
+    Ok(recorder)
+  }
+
+  /// Writes the audio captured since the last call to the file.
+  pub fn update(&mut self) -> Result<(), Error> {
+    let file = match &mut self.file {
+      Some(file) => file,
+      None => return Ok(()),
+    };
+    let mut bytes = Vec::new();
+    while let Some(frame) = self.ring.pop() {
+      bytes.extend_from_slice(&frame.to_le_bytes());
+    }
+    self.frames_written += (bytes.len() / 4) as u32;
+    file.write(&bytes)?;
+    Ok(())
+  }
+
+  /// The number of sample frames that were dropped because `update()` was not called often enough.
+  pub fn dropped_frames(&self) -> u32 {
+    self.ring.dropped.load(Ordering::Relaxed)
+  }
+  /// The length of the audio written to the file so far, in seconds.
+  pub fn recorded_seconds(&self) -> f32 {
+    self.frames_written as f32 / SAMPLE_FRAMES_PER_SEC as f32
+  }
+
+  /// Writes the remaining captured audio, and completes the WAV file.
+  pub fn finish(mut self) -> Result<(), Error> {
+    self.complete_file()
+  }
+
+  fn complete_file(&mut self) -> Result<(), Error> {
+    self.update()?;
+    if let Some(mut file) = self.file.take() {
+      // Rewrite the header with the size of the audio that was written.
+      file.seek(0, craydate_sys::SEEK_SET)?;
+      file.write(&wav_header(self.frames_written))?;
+      file.close()?;
+    }
+    Ok(())
+  }
+}
+impl Drop for AudioRecorder {
+  fn drop(&mut self) {
+    let _ = self.complete_file();
+    // Ensure the SoundEffect has a chance to detach from the channel before it is freed. The
+    // `SampleRing` is dropped after this, once the audio thread can no longer use it.
+    unsafe { ManuallyDrop::drop(&mut self.effect) };
+    unsafe { SoundEffect::fns().freeEffect.unwrap()(self.ptr.as_ptr()) };
+  }
+}
+impl core::fmt::Debug for AudioRecorder {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("AudioRecorder")
+      .field("frames_written", &self.frames_written)
+      .field("dropped_frames", &self.dropped_frames())
+      .finish()
+  }
+}
+
+impl AsRef<SoundEffect> for AudioRecorder {
+  fn as_ref(&self) -> &SoundEffect {
+    &self.effect
+  }
+}
+impl AsMut<SoundEffect> for AudioRecorder {
+  fn as_mut(&mut self) -> &mut SoundEffect {
+    &mut self.effect
+  }
+}
+
+/// A queue of stereo sample frames from the audio thread to the game's thread. Each frame is stored
+/// as the left and right 16-bit samples, in the order they appear in the WAV file.
+struct SampleRing {
+  frames: Box<[AtomicU32]>,
+  // The next frame to be written, which is only changed by the audio thread.
+  head: AtomicUsize,
+  // The next frame to be read, which is only changed by the game's thread.
+  tail: AtomicUsize,
+  dropped: AtomicU32,
+}
+impl SampleRing {
+  fn new() -> Self {
+    SampleRing {
+      frames: (0..RING_FRAMES).map(|_| AtomicU32::new(0)).collect(),
+      head: AtomicUsize::new(0),
+      tail: AtomicUsize::new(0),
+      dropped: AtomicU32::new(0),
+    }
+  }
+
+  /// Called from the audio thread.
+  fn push(&self, left: i16, right: i16) {
+    let head = self.head.load(Ordering::Relaxed);
+    let next = (head + 1) % RING_FRAMES;
+    if next == self.tail.load(Ordering::Acquire) {
+      self.dropped.fetch_add(1, Ordering::Relaxed);
+      return;
+    }
+    let frame = (left as u16 as u32) | ((right as u16 as u32) << 16);
+    self.frames[head].store(frame, Ordering::Relaxed);
+    self.head.store(next, Ordering::Release);
+  }
+
+  /// Called from the game's thread.
+  fn pop(&self) -> Option<u32> {
+    let tail = self.tail.load(Ordering::Relaxed);
+    if tail == self.head.load(Ordering::Acquire) {
+      return None;
+    }
+    let frame = self.frames[tail].load(Ordering::Relaxed);
+    self.tail.store((tail + 1) % RING_FRAMES, Ordering::Release);
+    Some(frame)
+  }
+}
+
+/// Converts a sample given to an effect, which is in Q8.24 fixed point format, to 16 bits.
+fn to_i16(sample: i32) -> i16 {
+  (sample >> 9).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+unsafe extern "C" fn c_tap_function(
+  effect: *mut CSoundEffect,
+  left: *mut i32,
+  right: *mut i32,
+  len: i32,
+  bufactive: i32,
+) -> i32 {
+  let ring = unsafe { &*(SoundEffect::fns().getUserdata.unwrap()(effect) as *const SampleRing) };
+  if bufactive == 0 {
+    // The buffers hold no sound in this cycle.
+    for _ in 0..len {
+      ring.push(0, 0);
+    }
+  } else {
+    let left = unsafe { core::slice::from_raw_parts(left, len as usize) };
+    let right = if right.is_null() {
+      left
+    } else {
+      unsafe { core::slice::from_raw_parts(right, len as usize) }
+    };
+    for (l, r) in left.iter().zip(right) {
+      ring.push(to_i16(*l), to_i16(*r));
+    }
+  }
+  // The buffers were not changed.
+  0
+}
+
+/// Makes the header of a 16-bit stereo WAV file holding `frames` sample frames.
+fn wav_header(frames: u32) -> [u8; WAV_HEADER_BYTES] {
+  const CHANNELS: u16 = 2;
+  const BYTES_PER_FRAME: u32 = 4;
+  let data_bytes = frames * BYTES_PER_FRAME;
+  let mut header = [0; WAV_HEADER_BYTES];
+  let fields: [&[u8]; 13] = [
+    b"RIFF",
+    &(data_bytes + WAV_HEADER_BYTES as u32 - 8).to_le_bytes(),
+    b"WAVE",
+    b"fmt ",
+    &16u32.to_le_bytes(),
+    // PCM samples.
+    &1u16.to_le_bytes(),
+    &CHANNELS.to_le_bytes(),
+    &(SAMPLE_FRAMES_PER_SEC as u32).to_le_bytes(),
+    &(SAMPLE_FRAMES_PER_SEC as u32 * BYTES_PER_FRAME).to_le_bytes(),
+    &(BYTES_PER_FRAME as u16).to_le_bytes(),
+    &16u16.to_le_bytes(),
+    b"data",
+    &data_bytes.to_le_bytes(),
+  ];
+  let mut i = 0;
+  for field in fields {
+    header[i..i + field.len()].copy_from_slice(field);
+    i += field.len();
+  }
+  header
+}
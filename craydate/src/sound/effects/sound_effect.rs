@@ -1,5 +1,6 @@
 use alloc::rc::Rc;
 use alloc::rc::Weak;
+use core::cell::Cell;
 use core::ptr::NonNull;
 
 use super::super::signals::synth_signal::SynthSignal;
@@ -13,6 +14,42 @@ enum Attachment {
   Channel(Weak<NonNull<CSoundChannel>>),
 }
 
+/// The mix level of a `SoundEffect`, which is shared with the `SoundChannel` it is attached to, so
+/// that the channel can change the mix of all of its effects together.
+#[derive(Debug)]
+pub(crate) struct EffectMix {
+  ptr: NonNull<CSoundEffect>,
+  // The mix level set on the effect itself.
+  mix: Cell<f32>,
+  bypassed: Cell<bool>,
+  // The mix modulator set on the effect, or null.
+  modulator: Cell<*mut CSynthSignalValue>,
+  // The wet level and bypass set on the channel the effect is attached to.
+  channel_mix: Cell<f32>,
+  channel_bypassed: Cell<bool>,
+}
+impl EffectMix {
+  pub(crate) fn set_channel_mix(&self, mix: f32, bypassed: bool) {
+    self.channel_mix.set(mix);
+    self.channel_bypassed.set(bypassed);
+    self.apply();
+  }
+
+  /// Sends the combined mix level to the effect.
+  fn apply(&self) {
+    let fns = SoundEffect::fns();
+    let ptr = self.ptr.as_ptr();
+    if self.bypassed.get() || self.channel_bypassed.get() {
+      // The modulator would override the mix level, so it is removed while bypassed.
+      unsafe { fns.setMixModulator.unwrap()(ptr, core::ptr::null_mut()) };
+      unsafe { fns.setMix.unwrap()(ptr, 0.0) };
+    } else {
+      unsafe { fns.setMixModulator.unwrap()(ptr, self.modulator.get()) };
+      unsafe { fns.setMix.unwrap()(ptr, self.mix.get() * self.channel_mix.get()) };
+    }
+  }
+}
+
 /// A `SoundEffect` can be attached to a `SoundChannel` to filter/mutate the sound being played on
 /// it. They all include a mix modulator that allows adjusting how much to mix the `SoundEffect`
 /// into the channel, between replacing the existing signal or leaving it unchanged.
@@ -21,18 +58,33 @@ enum Attachment {
 /// `AsRef<SoundEffect>` and `AsMut<SoundEffect>`. They also have `as_sound_effect()` and
 /// `as_sound_effect_mut()` methods, through the `AsSoundEffect` trait, to access the `SoundEffect`
 /// methods more easily.
+///
+/// An effect can be bypassed with `set_bypassed()`, which takes it out of the mix while leaving it
+/// attached to its channel, so it can be turned on and off at runtime, such as for an underwater
+/// filter. The mix of all effects on a channel can also be changed together through the
+/// `SoundChannel`.
 #[derive(Debug)]
 pub struct SoundEffect {
   ptr: NonNull<CSoundEffect>,
   attachment: Attachment,
   mix_modulator: Option<SynthSignal>,
+  mix: Rc<EffectMix>,
 }
 impl SoundEffect {
   pub(crate) fn from_ptr(ptr: *mut CSoundEffect) -> Self {
+    let ptr = NonNull::new(ptr).unwrap();
     SoundEffect {
-      ptr: NonNull::new(ptr).unwrap(),
+      ptr,
       attachment: Attachment::None,
       mix_modulator: None,
+      mix: Rc::new(EffectMix {
+        ptr,
+        mix: Cell::new(1.0),
+        bypassed: Cell::new(false),
+        modulator: Cell::new(core::ptr::null_mut()),
+        channel_mix: Cell::new(1.0),
+        channel_bypassed: Cell::new(false),
+      }),
     }
   }
 
@@ -41,8 +93,29 @@ impl SoundEffect {
   /// A level of 1 (full wet) replaces the input with the effect output; 0 leaves the effect out of
   /// the mix (which is useful if you’re using a delay line with taps and don’t want to hear the
   /// delay line itself).
+  ///
+  /// If the channel the effect is attached to has a mix level set through
+  /// `SoundChannel::set_effects_mix()`, the two levels are multiplied together.
   pub fn set_mix(&mut self, mix: f32) {
-    unsafe { Self::fns().setMix.unwrap()(self.cptr_mut(), mix) }
+    self.mix.mix.set(mix);
+    self.mix.apply();
+  }
+  /// Returns the wet/dry mix set for the effect with `set_mix()`.
+  pub fn mix(&self) -> f32 {
+    self.mix.mix.get()
+  }
+
+  /// Sets whether the effect is bypassed. A bypassed effect stays attached to its channel, but is
+  /// left out of the mix, as if its mix level was 0, until it is no longer bypassed.
+  ///
+  /// Any mix modulator is also ignored while the effect is bypassed.
+  pub fn set_bypassed(&mut self, bypassed: bool) {
+    self.mix.bypassed.set(bypassed);
+    self.mix.apply();
+  }
+  /// Returns whether the effect is bypassed by `set_bypassed()`.
+  pub fn is_bypassed(&self) -> bool {
+    self.mix.bypassed.get()
   }
 
   /// Sets a signal to modulate the effect’s mix level.
@@ -51,7 +124,8 @@ impl SoundEffect {
       // setMixModulator() takes a mutable pointer to the modulator but there is no visible state on
       // the modulator.
       signal.as_ref().cptr() as *mut _);
-    unsafe { Self::fns().setMixModulator.unwrap()(self.cptr_mut(), modulator_ptr) }
+    self.mix.modulator.set(modulator_ptr);
+    self.mix.apply();
     self.mix_modulator = signal.map(|signal| signal.as_ref().clone());
   }
   /// Gets the current signal modulating the effect’s mix level.
@@ -93,6 +167,10 @@ impl SoundEffect {
     }
   }
 
+  pub(crate) fn effect_mix(&self) -> &Rc<EffectMix> {
+    &self.mix
+  }
+
   pub(crate) fn cptr_mut(&mut self) -> *mut CSoundEffect {
     self.ptr.as_ptr()
   }
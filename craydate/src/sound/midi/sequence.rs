@@ -125,9 +125,9 @@ impl Sequence {
     finished_callback: SoundCompletionCallback<'a, T, F, Constructed>,
   ) {
     self.finished_callback = None;
-    let func = finished_callback.into_inner().and_then(|(callbacks, cb)| {
+    let func = finished_callback.into_inner().and_then(|(callbacks, cb, meta)| {
       let key = self.cptr_mut() as usize;
-      let (func, reg) = callbacks.add_sequence_finished(key, cb);
+      let (func, reg) = callbacks.add_sequence_finished(key, cb, meta);
       self.finished_callback = Some(reg);
       Some(func)
     });
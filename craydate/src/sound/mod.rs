@@ -1,3 +1,5 @@
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+pub(crate) mod audio_recorder;
 pub(crate) mod audio_sample;
 pub(crate) mod effects;
 pub(crate) mod headphone;
@@ -10,6 +12,8 @@ pub(crate) mod sound_format;
 pub(crate) mod sources;
 pub(crate) mod volume;
 
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+pub use audio_recorder::AudioRecorder;
 pub use audio_sample::AudioSample;
 pub use effects::bit_crusher::BitCrusher;
 pub use effects::delay_line::DelayLine;
@@ -40,11 +44,16 @@ pub use sources::sound_source::{AsSoundSource, SoundSource};
 pub use sources::synth::{Synth, SynthGenerator, SynthGeneratorVTable, SynthRender};
 pub use volume::{StereoVolume, Volume};
 
+use alloc::vec::Vec;
+
 use crate::callback_builder::{AllowNull, CallbackBuilder, CallbackBuilderWithArg, Constructed};
 use crate::capi_state::CApiState;
-use crate::time::TimeTicks;
+use crate::ctypes_enums::SoundWaveform;
+use crate::time::{TimeDelta, TimeTicks};
 
 pub(crate) const SAMPLE_FRAMES_PER_SEC: i32 = 44_100;
+/// The most beeps from `Sound::beep()` that can play at once.
+const MAX_BEEPS: usize = 4;
 
 /// A callback builder for a closure to be called on sound completion events.
 pub type SoundCompletionCallback<'a, T, F, S> = CallbackBuilder<'a, T, F, AllowNull, S>;
@@ -57,6 +66,10 @@ pub type HeadphoneChangeCallback<'a, T, F, S> =
 #[derive(Debug)]
 pub struct Sound {
   default_channel: SoundChannel,
+  // The synths used to play `beep()`, which are made as needed, and reused once they finish playing.
+  beep_synths: Vec<Synth>,
+  // The synth to replace next when all of them are playing, which cycles through the pool.
+  next_beep: usize,
 }
 impl Sound {
   pub(crate) fn new() -> Self {
@@ -64,6 +77,8 @@ impl Sound {
       default_channel: SoundChannel::new_system_channel(unsafe {
         Self::fns().getDefaultChannel.unwrap()()
       }),
+      beep_synths: Vec::new(),
+      next_beep: 0,
     }
   }
 
@@ -76,6 +91,39 @@ impl Sound {
     &mut self.default_channel
   }
 
+  /// Plays a note of the `waveform` at `frequency`, in Hz, for `duration` on the default channel.
+  ///
+  /// This is meant for quick sounds, such as UI blips, or while prototyping, without having to build
+  /// and hold onto a `Synth`. The beeps are played by a small pool of synths that is managed
+  /// internally. Up to 4 beeps can play at once, and beyond that, a new beep cuts off one of the
+  /// beeps that are playing.
+  pub fn beep(
+    &mut self,
+    frequency: f32,
+    duration: TimeDelta,
+    volume: Volume,
+    waveform: SoundWaveform,
+  ) {
+    let index = match self.beep_synths.iter().position(|s| !s.as_ref().is_playing()) {
+      Some(index) => index,
+      None if self.beep_synths.len() < MAX_BEEPS => {
+        let mut synth = Synth::new_with_waveform(waveform);
+        // The synth is new, so it can not already be attached elsewhere.
+        self.default_channel.add_source(&mut synth).unwrap();
+        self.beep_synths.push(synth);
+        self.beep_synths.len() - 1
+      }
+      None => {
+        let index = self.next_beep;
+        self.next_beep = (self.next_beep + 1) % MAX_BEEPS;
+        index
+      }
+    };
+    let synth = &mut self.beep_synths[index];
+    synth.set_waveform(waveform);
+    synth.play_frequency_note(frequency, volume, Some(duration), None);
+  }
+
   /// Add a user-created `SoundChannel` to have it play from the device.
   pub fn add_channel(&mut self, channel: &mut SoundChannel) {
     if !channel.is_system_channel() {
@@ -171,8 +219,8 @@ impl Sound {
     let mut headphone_callback = CApiState::get().headphone_change_callback.borrow_mut();
     *headphone_callback = None;
 
-    let func = change_callback.into_inner().and_then(|(callbacks, cb)| {
-      let (func, reg) = callbacks.add_headphone_change(cb);
+    let func = change_callback.into_inner().and_then(|(callbacks, cb, meta)| {
+      let (func, reg) = callbacks.add_headphone_change(cb, meta);
       *headphone_callback = Some(reg);
       Some(func)
     });
@@ -1,7 +1,8 @@
-use alloc::rc::Rc;
+use alloc::rc::{Rc, Weak};
+use alloc::vec::Vec;
 use core::ptr::NonNull;
 
-use super::effects::sound_effect::SoundEffect;
+use super::effects::sound_effect::{EffectMix, SoundEffect};
 use super::signals::synth_signal::{SynthSignal, SynthSignalSubclass};
 use super::sources::sound_source::SoundSource;
 use super::volume::Volume;
@@ -28,6 +29,11 @@ pub struct SoundChannel {
   pan_modulator: Option<SynthSignal>,
   dry_level_signal: SynthSignal,
   wet_level_signal: SynthSignal,
+  // The mix levels of the effects attached to the channel. An effect that is dropped while attached
+  // leaves behind a dead Weak pointer here, which is removed when the list is next used.
+  effects: Vec<Weak<EffectMix>>,
+  effects_mix: f32,
+  effects_bypassed: bool,
 }
 impl SoundChannel {
   fn from_ptr(ptr: *mut CSoundChannel, owned: bool) -> SoundChannel {
@@ -47,6 +53,9 @@ impl SoundChannel {
       pan_modulator: None,
       dry_level_signal,
       wet_level_signal,
+      effects: Vec::new(),
+      effects_mix: 1.0,
+      effects_bypassed: false,
     }
   }
 
@@ -91,7 +100,13 @@ impl SoundChannel {
     &mut self,
     sound_effect: &mut T,
   ) -> Result<(), Error> {
-    sound_effect.as_mut().attach_to_channel(&self.ptr)
+    let effect = sound_effect.as_mut();
+    effect.attach_to_channel(&self.ptr)?;
+    let mix = effect.effect_mix();
+    mix.set_channel_mix(self.effects_mix, self.effects_bypassed);
+    self.effects.retain(|e| e.strong_count() > 0);
+    self.effects.push(Rc::downgrade(mix));
+    Ok(())
   }
   /// Remove the `sound_effect` from this channel.
   ///
@@ -101,7 +116,50 @@ impl SoundChannel {
     &mut self,
     sound_effect: &mut T,
   ) -> Result<(), Error> {
-    sound_effect.as_mut().detach_from_channel(&self.ptr)
+    let effect = sound_effect.as_mut();
+    effect.detach_from_channel(&self.ptr)?;
+    let mix = effect.effect_mix();
+    mix.set_channel_mix(1.0, false);
+    self.effects.retain(|e| e.strong_count() > 0 && !e.ptr_eq(&Rc::downgrade(mix)));
+    Ok(())
+  }
+
+  /// Sets the wet/dry mix for all of the effects attached to the channel, including any attached
+  /// later, from 0 (all dry) to 1 (as wet as each effect's own mix level).
+  ///
+  /// This is multiplied with each effect's mix level from `SoundEffect::set_mix()`, so the effects
+  /// keep their balance with each other. An effect with a mix modulator is controlled by the
+  /// modulator instead.
+  pub fn set_effects_mix(&mut self, mix: f32) {
+    self.effects_mix = mix;
+    self.update_effects();
+  }
+  /// Returns the wet/dry mix for all of the effects attached to the channel, which is set by
+  /// `set_effects_mix()`.
+  pub fn effects_mix(&self) -> f32 {
+    self.effects_mix
+  }
+
+  /// Sets whether all of the effects attached to the channel, including any attached later, are
+  /// bypassed. This allows hearing the channel with and without its effects, without removing them.
+  ///
+  /// This is separate from bypassing each effect with `SoundEffect::set_bypassed()`. An effect is
+  /// bypassed if either is set.
+  pub fn set_effects_bypassed(&mut self, bypassed: bool) {
+    self.effects_bypassed = bypassed;
+    self.update_effects();
+  }
+  /// Returns whether all of the effects attached to the channel are bypassed by
+  /// `set_effects_bypassed()`.
+  pub fn effects_bypassed(&self) -> bool {
+    self.effects_bypassed
+  }
+
+  fn update_effects(&mut self) {
+    self.effects.retain(|e| e.strong_count() > 0);
+    for mix in self.effects.iter().filter_map(|e| e.upgrade()) {
+      mix.set_channel_mix(self.effects_mix, self.effects_bypassed);
+    }
   }
 
   /// Gets the volume for the channel, in the range [0-1].
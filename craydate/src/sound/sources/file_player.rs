@@ -160,9 +160,9 @@ impl FilePlayer {
     completion_callback: SoundCompletionCallback<'a, T, F, Constructed>,
   ) {
     self.fade_callback = None;
-    let func = completion_callback.into_inner().and_then(|(callbacks, cb)| {
+    let func = completion_callback.into_inner().and_then(|(callbacks, cb, meta)| {
       let key = self.as_source_mut().cptr() as usize;
-      let (func, reg) = callbacks.add_sound_source_completion(key, cb);
+      let (func, reg) = callbacks.add_sound_source_completion(key, cb, meta);
       self.fade_callback = Some(reg);
       Some(func)
     });
@@ -131,10 +131,10 @@ impl SamplePlayer<'_> {
     loop_callback: SoundCompletionCallback<'a, T, F, Constructed>,
   ) {
     self.loop_callback = None;
-    let func = loop_callback.into_inner().and_then(|(callbacks, cb)| {
+    let func = loop_callback.into_inner().and_then(|(callbacks, cb, meta)| {
       // This pointer is not aligned, but we will not deref it. It's only used as a map key.
       let key = unsafe { self.as_source_mut().cptr().add(1) } as usize;
-      let (func, reg) = callbacks.add_sound_source_completion(key, cb);
+      let (func, reg) = callbacks.add_sound_source_completion(key, cb, meta);
       self.loop_callback = Some(reg);
       Some(func)
     });
@@ -149,9 +149,9 @@ impl SoundSource {
     completion_callback: SoundCompletionCallback<'a, T, F, Constructed>,
   ) {
     self.completion_callback = None;
-    let func = completion_callback.into_inner().and_then(|(callbacks, cb)| {
+    let func = completion_callback.into_inner().and_then(|(callbacks, cb, meta)| {
       let key = self.cptr_mut() as usize;
-      let (func, reg) = callbacks.add_sound_source_completion(key, cb);
+      let (func, reg) = callbacks.add_sound_source_completion(key, cb, meta);
       self.completion_callback = Some(reg);
       Some(func)
     });
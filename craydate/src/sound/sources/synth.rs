@@ -47,10 +47,15 @@ impl Synth {
   /// Creates a new Synth that plays a waveform.
   pub fn new_with_waveform(waveform: SoundWaveform) -> Self {
     let mut synth = Self::new();
-    unsafe { Self::fns().setWaveform.unwrap()(synth.cptr_mut(), waveform) };
+    synth.set_waveform(waveform);
     synth
   }
 
+  /// Changes the waveform of a Synth that was made with `new_with_waveform()`.
+  pub(crate) fn set_waveform(&mut self, waveform: SoundWaveform) {
+    unsafe { Self::fns().setWaveform.unwrap()(self.cptr_mut(), waveform) };
+  }
+
   /// Creates a new Synth that plays a sample.
   ///
   /// An optional sustain region defines a loop to play while the note is on. Sample data must be
@@ -1,4 +1,5 @@
 use alloc::rc::Rc;
+use alloc::string::String;
 use core::cell::Cell;
 use core::future::Future;
 use core::pin::Pin;
@@ -48,6 +49,9 @@ pub enum SystemEvent {
     /// The released keycode.
     keycode: u32,
   },
+  /// Event when a file being watched with `File::watch_data_file()` was modified, created or
+  /// deleted, holding the path that was given to `watch_data_file()`. Does not occur on device.
+  DataFileChanged(String),
   /// A system callback is active, and the game can execute their registered closure for it by
   /// running their `Callbacks` object(s).
   Callback,